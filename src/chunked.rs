@@ -0,0 +1,294 @@
+use crate::{ManifestRoot, PoseidonError, PoseidonResult, UserData};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{ErrorKind, Read, Write};
+use subtle::ConstantTimeEq;
+
+/// 1 MiB
+pub const DEFAULT_CHUNK_SIZE: u32 = 1024 * 1024;
+
+/// A slice of a large [UserData] payload
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct UserDataChunk {
+    pub index: u32,
+    pub data: Vec<u8>,
+}
+
+impl UserDataChunk {
+    /// The SHA-256 hash of the chunk bytes
+    pub fn hash(&self) -> [u8; 32] {
+        Sha256::digest(&self.data).into()
+    }
+}
+
+/// Describes a payload split into fixed size chunks. Every chunk except the
+/// last one is exactly `chunk_size` bytes long.
+///
+/// `root` is the SHA-256 hash of the borsh encoding of
+/// `(mime, total_len, chunk_size, chunk_hashes)`, so it commits to the
+/// content, order and size of every chunk. It differs from the [crate::DataID]
+/// of the reassembled [UserData], which needs the length of the data before
+/// the data and so cannot be computed while streaming.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    pub mime: String,
+    pub total_len: u64,
+    pub chunk_size: u32,
+    pub chunk_hashes: Vec<[u8; 32]>,
+    pub root: ManifestRoot,
+}
+
+impl ChunkManifest {
+    pub fn compute_root(
+        mime: &str,
+        total_len: u64,
+        chunk_size: u32,
+        chunk_hashes: &[[u8; 32]],
+    ) -> PoseidonResult<ManifestRoot> {
+        let mut hasher = Sha256::new();
+        BorshSerialize::serialize(&(mime, total_len, chunk_size, chunk_hashes), &mut hasher)?;
+
+        Ok(hasher.finalize().into())
+    }
+
+    /// Checks that the chunk count matches `total_len` and that `root`
+    /// commits to the other fields
+    pub fn verify(&self) -> PoseidonResult<()> {
+        if self.chunk_size == 0 {
+            return Err(PoseidonError::InvalidChunkManifest(
+                "chunk size is zero".to_owned(),
+            ));
+        }
+
+        let expected_chunks = self.total_len.div_ceil(u64::from(self.chunk_size));
+        if u64::from(self.chunk_count()?) != expected_chunks {
+            return Err(PoseidonError::InvalidChunkManifest(format!(
+                "expected `{}` chunks for `{}` bytes, found `{}`",
+                expected_chunks,
+                self.total_len,
+                self.chunk_hashes.len()
+            )));
+        }
+
+        let root = Self::compute_root(
+            &self.mime,
+            self.total_len,
+            self.chunk_size,
+            &self.chunk_hashes,
+        )?;
+        if bool::from(root.ct_eq(&self.root)) {
+            Ok(())
+        } else {
+            Err(PoseidonError::InvalidChunkManifest(
+                "the root does not match the other fields".to_owned(),
+            ))
+        }
+    }
+
+    pub fn chunk_count(&self) -> PoseidonResult<u32> {
+        u32::try_from(self.chunk_hashes.len()).map_err(|_| too_many_chunks())
+    }
+
+    /// The length a chunk at `index` must have
+    pub fn chunk_len(&self, index: u32) -> u64 {
+        let offset = u64::from(index) * u64::from(self.chunk_size);
+
+        self.total_len
+            .saturating_sub(offset)
+            .min(u64::from(self.chunk_size))
+    }
+}
+
+/// Splits any [Read] into [UserDataChunk]s without buffering more than one
+/// chunk. Once the iterator is exhausted [ChunkReader::into_manifest]
+/// returns the manifest describing the chunks that were produced.
+pub struct ChunkReader<R: Read> {
+    mime: String,
+    reader: R,
+    chunk_size: u32,
+    total_len: u64,
+    chunk_hashes: Vec<[u8; 32]>,
+    exhausted: bool,
+}
+
+impl<R: Read> ChunkReader<R> {
+    pub fn new(mime: &str, reader: R, chunk_size: u32) -> PoseidonResult<Self> {
+        if chunk_size == 0 {
+            return Err(PoseidonError::InvalidChunkManifest(
+                "chunk size is zero".to_owned(),
+            ));
+        }
+
+        Ok(ChunkReader {
+            mime: mime.to_owned(),
+            reader,
+            chunk_size,
+            total_len: 0,
+            chunk_hashes: Vec::new(),
+            exhausted: false,
+        })
+    }
+
+    /// Builds the manifest, returning [PoseidonError::ChunkStreamIncomplete]
+    /// if the reader has not been read to the end
+    pub fn into_manifest(self) -> PoseidonResult<ChunkManifest> {
+        if !self.exhausted {
+            return Err(PoseidonError::ChunkStreamIncomplete);
+        }
+
+        let root = ChunkManifest::compute_root(
+            &self.mime,
+            self.total_len,
+            self.chunk_size,
+            &self.chunk_hashes,
+        )?;
+
+        Ok(ChunkManifest {
+            mime: self.mime,
+            total_len: self.total_len,
+            chunk_size: self.chunk_size,
+            chunk_hashes: self.chunk_hashes,
+            root,
+        })
+    }
+
+    fn read_chunk(&mut self) -> PoseidonResult<Option<UserDataChunk>> {
+        let mut data = vec![0u8; self.chunk_size as usize];
+        let mut filled = 0;
+
+        while filled < data.len() {
+            match self.reader.read(&mut data[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        if filled < data.len() {
+            self.exhausted = true;
+        }
+        if filled == 0 {
+            return Ok(None);
+        }
+        data.truncate(filled);
+
+        let index = u32::try_from(self.chunk_hashes.len()).map_err(|_| too_many_chunks())?;
+        let chunk = UserDataChunk { index, data };
+        self.total_len += filled as u64;
+        self.chunk_hashes.push(chunk.hash());
+
+        Ok(Some(chunk))
+    }
+}
+
+impl<R: Read> Iterator for ChunkReader<R> {
+    type Item = PoseidonResult<UserDataChunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted {
+            return None;
+        }
+
+        match self.read_chunk() {
+            Ok(chunk) => chunk.map(Ok),
+            Err(error) => {
+                self.exhausted = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// Writes chunks described by a [ChunkManifest] to any [Write], checking the
+/// order, length and hash of every chunk as it arrives.
+pub struct ChunkAssembler<W: Write> {
+    manifest: ChunkManifest,
+    writer: W,
+    next_index: u32,
+}
+
+impl<W: Write> ChunkAssembler<W> {
+    pub fn new(manifest: ChunkManifest, writer: W) -> PoseidonResult<Self> {
+        manifest.verify()?;
+
+        Ok(ChunkAssembler {
+            manifest,
+            writer,
+            next_index: 0,
+        })
+    }
+
+    pub fn manifest(&self) -> &ChunkManifest {
+        &self.manifest
+    }
+
+    pub fn write_chunk(&mut self, chunk: &UserDataChunk) -> PoseidonResult<()> {
+        if chunk.index != self.next_index || chunk.index >= self.manifest.chunk_count()? {
+            return Err(PoseidonError::ChunkOutOfOrder {
+                expected: self.next_index,
+                found: chunk.index,
+            });
+        }
+
+        let expected_hash = &self.manifest.chunk_hashes[chunk.index as usize];
+        if chunk.data.len() as u64 != self.manifest.chunk_len(chunk.index)
+            || !bool::from(chunk.hash().ct_eq(expected_hash))
+        {
+            return Err(PoseidonError::ChunkIntegrityMismatch { index: chunk.index });
+        }
+
+        self.writer.write_all(&chunk.data)?;
+        self.next_index += 1;
+
+        Ok(())
+    }
+
+    /// Flushes and returns the writer once every chunk has been written
+    pub fn finish(mut self) -> PoseidonResult<W> {
+        if self.next_index != self.manifest.chunk_count()? {
+            return Err(PoseidonError::ChunkStreamIncomplete);
+        }
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+impl UserData {
+    /// Splits the data into chunks and the manifest describing them
+    pub fn to_chunks(
+        &self,
+        chunk_size: u32,
+    ) -> PoseidonResult<(ChunkManifest, Vec<UserDataChunk>)> {
+        let mut reader = ChunkReader::new(&self.mime, self.data.as_slice(), chunk_size)?;
+        let chunks = reader
+            .by_ref()
+            .collect::<PoseidonResult<Vec<UserDataChunk>>>()?;
+
+        Ok((reader.into_manifest()?, chunks))
+    }
+
+    /// Reassembles chunks produced by [UserData::to_chunks] or a [ChunkReader]
+    pub fn from_chunks<'a, I>(manifest: ChunkManifest, chunks: I) -> PoseidonResult<UserData>
+    where
+        I: IntoIterator<Item = &'a UserDataChunk>,
+    {
+        let mime = manifest.mime.clone();
+        let mut assembler = ChunkAssembler::new(manifest, Vec::new())?;
+
+        for chunk in chunks {
+            assembler.write_chunk(chunk)?;
+        }
+
+        Ok(UserData {
+            mime,
+            data: assembler.finish()?,
+        })
+    }
+}
+
+fn too_many_chunks() -> PoseidonError {
+    PoseidonError::InvalidChunkManifest(format!("more than `{}` chunks", u32::MAX))
+}
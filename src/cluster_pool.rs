@@ -0,0 +1,251 @@
+use crate::{Cluster, PoseidonError, PoseidonResult, RpcClient};
+use core::time::Duration;
+
+/// How far, in slots, an endpoint may trail the most advanced endpoint of the
+/// pool before it is considered lagging
+pub const DEFAULT_MAX_SLOT_LAG: u64 = 50;
+
+/// The timeout of the health checks
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The outcome of the last health check or request of an endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndpointHealth {
+    /// Not probed yet
+    Unknown,
+    Healthy {
+        slot: u64,
+    },
+    /// The node answers but trails the other nodes of the pool
+    Lagging {
+        slot: u64,
+        behind: u64,
+    },
+    Unhealthy {
+        reason: String,
+    },
+}
+
+impl EndpointHealth {
+    /// Endpoints are picked from the lowest rank that has any
+    fn rank(&self) -> u8 {
+        match self {
+            EndpointHealth::Healthy { .. } => 0,
+            EndpointHealth::Unknown => 1,
+            EndpointHealth::Lagging { .. } => 2,
+            EndpointHealth::Unhealthy { .. } => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PoolEndpoint {
+    cluster: Cluster,
+    weight: u32,
+    health: EndpointHealth,
+    /// Smooth weighted round robin state, see [ClusterPool::pick]
+    current_weight: i64,
+}
+
+impl PoolEndpoint {
+    pub fn cluster(&self) -> &Cluster {
+        &self.cluster
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn health(&self) -> &EndpointHealth {
+        &self.health
+    }
+}
+
+/// Several RPC endpoints used as one. Requests go to the healthiest
+/// endpoints in proportion to their weight and fail over to the next
+/// endpoint when the connection or the node fails.
+///
+/// Endpoints that fail are not used again until [ClusterPool::probe] finds
+/// them healthy, so the pool should be probed periodically.
+#[derive(Debug, Clone)]
+pub struct ClusterPool {
+    endpoints: Vec<PoolEndpoint>,
+    max_slot_lag: u64,
+    probe_timeout: Duration,
+}
+
+impl Default for ClusterPool {
+    fn default() -> Self {
+        ClusterPool::new()
+    }
+}
+
+impl ClusterPool {
+    pub fn new() -> Self {
+        ClusterPool {
+            endpoints: Vec::new(),
+            max_slot_lag: DEFAULT_MAX_SLOT_LAG,
+            probe_timeout: DEFAULT_PROBE_TIMEOUT,
+        }
+    }
+
+    /// Adds an endpoint that receives `weight` requests for every request of
+    /// an endpoint of weight `1`. A weight of `0` is treated as `1`.
+    pub fn with_endpoint(mut self, cluster: Cluster, weight: u32) -> Self {
+        self.endpoints.push(PoolEndpoint {
+            cluster,
+            weight: weight.max(1),
+            health: EndpointHealth::Unknown,
+            current_weight: 0,
+        });
+
+        self
+    }
+
+    pub fn with_max_slot_lag(mut self, max_slot_lag: u64) -> Self {
+        self.max_slot_lag = max_slot_lag;
+
+        self
+    }
+
+    pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+        self.probe_timeout = probe_timeout;
+
+        self
+    }
+
+    pub fn endpoints(&self) -> &[PoolEndpoint] {
+        &self.endpoints
+    }
+
+    /// Checks every endpoint with `getHealth` and `getSlot`. Endpoints more
+    /// than the max slot lag behind the most advanced one are lagging.
+    pub fn probe(&mut self) {
+        for endpoint in &mut self.endpoints {
+            endpoint.health = match probe_endpoint(&endpoint.cluster, self.probe_timeout) {
+                Ok(slot) => EndpointHealth::Healthy { slot },
+                Err(error) => EndpointHealth::Unhealthy {
+                    reason: error.to_string(),
+                },
+            };
+        }
+
+        let highest_slot = self
+            .endpoints
+            .iter()
+            .filter_map(|endpoint| match endpoint.health {
+                EndpointHealth::Healthy { slot } => Some(slot),
+                _ => None,
+            })
+            .max();
+
+        if let Some(highest_slot) = highest_slot {
+            for endpoint in &mut self.endpoints {
+                if let EndpointHealth::Healthy { slot } = endpoint.health {
+                    let behind = highest_slot - slot;
+                    if behind > self.max_slot_lag {
+                        endpoint.health = EndpointHealth::Lagging { slot, behind };
+                    }
+                }
+            }
+        }
+    }
+
+    /// The cluster the next request should go to
+    pub fn pick(&mut self) -> PoseidonResult<&Cluster> {
+        let index = self.pick_index().ok_or(PoseidonError::NoHealthyEndpoint)?;
+
+        Ok(&self.endpoints[index].cluster)
+    }
+
+    /// Sends `request` to the picked endpoint. An endpoint that cannot be
+    /// reached or reports itself unhealthy is marked unhealthy and the
+    /// request is sent to the next one, until no endpoint is left. Any other
+    /// outcome, including transaction errors, is returned as is.
+    pub fn call<T, F>(&mut self, mut request: F) -> PoseidonResult<T>
+    where
+        F: FnMut(&Cluster) -> PoseidonResult<T>,
+    {
+        let mut last_error = None;
+
+        while let Some(index) = self.pick_index() {
+            let endpoint = &mut self.endpoints[index];
+
+            match request(&endpoint.cluster) {
+                Err(error) if is_endpoint_failure(&error) => {
+                    endpoint.health = EndpointHealth::Unhealthy {
+                        reason: error.to_string(),
+                    };
+                    last_error = Some(error);
+                }
+                outcome => return outcome,
+            }
+        }
+
+        Err(last_error.unwrap_or(PoseidonError::NoHealthyEndpoint))
+    }
+
+    /// Smooth weighted round robin, as in nginx, among the endpoints of the
+    /// best health. Every candidate gains its weight, the one with the most
+    /// is picked and loses the total weight of the candidates.
+    fn pick_index(&mut self) -> Option<usize> {
+        let best_rank = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| !matches!(endpoint.health, EndpointHealth::Unhealthy { .. }))
+            .map(|endpoint| endpoint.health.rank())
+            .min()?;
+
+        let mut total_weight = 0;
+        let mut picked: Option<usize> = None;
+        for index in 0..self.endpoints.len() {
+            let endpoint = &mut self.endpoints[index];
+            if endpoint.health.rank() != best_rank {
+                continue;
+            }
+
+            endpoint.current_weight += i64::from(endpoint.weight);
+            total_weight += i64::from(endpoint.weight);
+
+            let current_weight = endpoint.current_weight;
+            let is_heaviest = match picked {
+                Some(picked) => current_weight > self.endpoints[picked].current_weight,
+                None => true,
+            };
+            if is_heaviest {
+                picked = Some(index);
+            }
+        }
+
+        let index = picked?;
+        self.endpoints[index].current_weight -= total_weight;
+
+        Some(index)
+    }
+}
+
+/// Transient failures of the connection or of the node itself, after which
+/// another endpoint may succeed. HTTP 429 and 503 answers are I/O errors,
+/// see [PoseidonError::from_http_status]. Transaction errors are about the
+/// transaction and would fail the same on every endpoint.
+fn is_endpoint_failure(error: &PoseidonError) -> bool {
+    let is_transport_or_node = match error.root_cause() {
+        PoseidonError::IoErr(..)
+        | PoseidonError::Http(..)
+        | PoseidonError::Rustls(..)
+        | PoseidonError::NodeUnhealthy(..) => true,
+        // Node unhealthy
+        PoseidonError::Json(error) => error.code == -32005,
+        _ => false,
+    };
+
+    is_transport_or_node && error.is_retryable()
+}
+
+/// The slot of a healthy node
+fn probe_endpoint(cluster: &Cluster, timeout: Duration) -> PoseidonResult<u64> {
+    let client = RpcClient::new(cluster.clone()).with_timeout(timeout);
+    client.get_health()?;
+
+    client.get_slot()
+}
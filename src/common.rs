@@ -1,10 +1,7 @@
 use serde::{Deserialize, Serialize};
 use borsh::{BorshDeserialize, BorshSerialize};
-use crate::PoseidonError;
-
-pub type Ed25519Keypair = [u8; 64];
-pub type Ed25519Signature = [u8; 64];
-pub type Ed25519PublicKey = [u8; 32];
+use core::{fmt, str::FromStr};
+use crate::{PoseidonError, PoseidonResult};
 
 pub type Base58PublicKey = String;
 pub type Base58Signature = String;
@@ -18,33 +15,368 @@ pub type UnixTimestamp = i64;
 pub type EncryptedData = Vec<u8>;
 
 pub type DataID = [u8; 32];
+/// The root hash of a [crate::ChunkManifest]. It is not the [DataID] of the
+/// chunked payload.
+pub type ManifestRoot = [u8; 32];
 pub type TokenID = [u8; 32];
 pub type SubscriptionID = [u8; 32];
 pub type DataOwnedBytes = Vec<u8>;
 pub type DataBytes<'a> = &'a [u8];
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, PartialOrd)]
+/// A Solana cluster. Variants are only ever appended so that the serde and
+/// borsh encodings of the existing ones do not change. Custom clusters are
+/// validated when they are deserialized.
+#[derive(
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[serde(try_from = "UncheckedCluster")]
 pub enum Cluster {
     MainnetBeta,
     MainnetBetaSerum,
     Testnet,
+    #[default]
     Devnet,
+    /// A `solana-test-validator` running on this machine
+    Localnet,
+    /// Any other RPC node, e.g. a private RPC provider. `ws_url` is only
+    /// needed when the pubsub endpoint is not served next to `rpc_url`.
+    Custom {
+        rpc_url: String,
+        ws_url: Option<String>,
+    },
 }
 
 impl Cluster {
-    pub fn url(&self) -> &'static str {
+    /// A custom cluster, after checking that `rpc_url` is an `http(s)` URL
+    /// and `ws_url` a `ws(s)` URL
+    pub fn custom<R: Into<String>>(rpc_url: R, ws_url: Option<String>) -> PoseidonResult<Cluster> {
+        let cluster = Cluster::Custom {
+            rpc_url: rpc_url.into(),
+            ws_url,
+        };
+        cluster.validate()?;
+
+        Ok(cluster)
+    }
+
+    /// Checks the URLs of a custom cluster, e.g. one built from its fields
+    pub fn validate(&self) -> PoseidonResult<()> {
+        if let Cluster::Custom { rpc_url, ws_url } = self {
+            validate_url(rpc_url, &["http", "https"])?;
+
+            if let Some(ws_url) = ws_url {
+                validate_url(ws_url, &["ws", "wss"])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The RPC endpoint
+    pub fn url(&self) -> &str {
         match self {
             Cluster::MainnetBeta => "https://api.mainnet-beta.solana.com",
             Cluster::MainnetBetaSerum => "https://solana-api.projectserum.com",
             Cluster::Testnet => "https://api.testnet.solana.com",
             Cluster::Devnet => "https://api.devnet.solana.com",
+            Cluster::Localnet => "http://127.0.0.1:8899",
+            Cluster::Custom { rpc_url, .. } => rpc_url,
+        }
+    }
+
+    /// The pubsub endpoint for account and signature subscriptions, the
+    /// `ws_url` of a custom cluster or the one derived from its RPC URL
+    pub fn ws_url(&self) -> String {
+        match self {
+            Cluster::Custom {
+                ws_url: Some(ws_url),
+                ..
+            } => ws_url.clone(),
+            cluster => derive_ws_url(cluster.url()),
+        }
+    }
+
+    /// The hash of the genesis block, to check with `getGenesisHash` that an
+    /// endpoint serves the expected cluster. Local and custom clusters have
+    /// no known genesis.
+    pub fn genesis_hash(&self) -> Option<&'static str> {
+        match self {
+            Cluster::MainnetBeta | Cluster::MainnetBetaSerum => {
+                Some("5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d")
+            }
+            Cluster::Testnet => Some("4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY"),
+            Cluster::Devnet => Some("EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG"),
+            Cluster::Localnet | Cluster::Custom { .. } => None,
+        }
+    }
+
+    /// The cluster of an RPC URL. The URLs of the built-in clusters, and
+    /// `localhost:8899`, give back the built-in cluster.
+    pub fn from_url(url: &str) -> PoseidonResult<Cluster> {
+        validate_url(url, &["http", "https"])?;
+
+        let trimmed = url.trim_end_matches('/');
+        let cluster = [
+            Cluster::MainnetBeta,
+            Cluster::MainnetBetaSerum,
+            Cluster::Testnet,
+            Cluster::Devnet,
+            Cluster::Localnet,
+        ]
+        .into_iter()
+        .find(|cluster| cluster.url().eq_ignore_ascii_case(trimmed));
+
+        Ok(match cluster {
+            Some(cluster) => cluster,
+            None if trimmed.eq_ignore_ascii_case("http://localhost:8899") => Cluster::Localnet,
+            None => Cluster::Custom {
+                rpc_url: url.to_owned(),
+                ws_url: None,
+            },
+        })
+    }
+}
+
+/// The encoded form of [Cluster], which is only a cluster once its URLs are
+/// checked
+#[derive(Deserialize, BorshDeserialize)]
+#[serde(rename = "Cluster")]
+enum UncheckedCluster {
+    MainnetBeta,
+    MainnetBetaSerum,
+    Testnet,
+    Devnet,
+    Localnet,
+    Custom {
+        rpc_url: String,
+        ws_url: Option<String>,
+    },
+}
+
+impl TryFrom<UncheckedCluster> for Cluster {
+    type Error = PoseidonError;
+
+    fn try_from(cluster: UncheckedCluster) -> PoseidonResult<Cluster> {
+        match cluster {
+            UncheckedCluster::MainnetBeta => Ok(Cluster::MainnetBeta),
+            UncheckedCluster::MainnetBetaSerum => Ok(Cluster::MainnetBetaSerum),
+            UncheckedCluster::Testnet => Ok(Cluster::Testnet),
+            UncheckedCluster::Devnet => Ok(Cluster::Devnet),
+            UncheckedCluster::Localnet => Ok(Cluster::Localnet),
+            UncheckedCluster::Custom { rpc_url, ws_url } => Cluster::custom(rpc_url, ws_url),
+        }
+    }
+}
+
+impl BorshDeserialize for Cluster {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        <UncheckedCluster as BorshDeserialize>::deserialize(buf)?
+            .try_into()
+            .map_err(|error: PoseidonError| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string())
+            })
+    }
+}
+
+/// Monikers for the built-in clusters, the RPC URL for custom ones
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cluster::MainnetBeta => f.write_str("mainnet-beta"),
+            Cluster::MainnetBetaSerum => f.write_str("mainnet-beta-serum"),
+            Cluster::Testnet => f.write_str("testnet"),
+            Cluster::Devnet => f.write_str("devnet"),
+            Cluster::Localnet => f.write_str("localnet"),
+            Cluster::Custom { rpc_url, .. } => f.write_str(rpc_url),
+        }
+    }
+}
+
+/// Accepts the monikers of the Solana CLI, `mainnet-beta`, `testnet`,
+/// `devnet`, `localhost` and their first letter, as well as RPC URLs
+impl FromStr for Cluster {
+    type Err = PoseidonError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "mainnet-beta" | "mainnet" | "m" => Ok(Cluster::MainnetBeta),
+            "mainnet-beta-serum" | "serum" => Ok(Cluster::MainnetBetaSerum),
+            "testnet" | "t" => Ok(Cluster::Testnet),
+            "devnet" | "d" => Ok(Cluster::Devnet),
+            "localnet" | "localhost" | "l" => Ok(Cluster::Localnet),
+            url if url.contains("://") => Cluster::from_url(url),
+            moniker => Err(PoseidonError::InvalidCluster(format!(
+                "unknown cluster `{}`",
+                moniker
+            ))),
+        }
+    }
+}
+
+/// The parts of a URL the cluster endpoints are derived from. This is not a
+/// full URL parser, only enough to catch typos and swap schemes and ports.
+struct UrlParts<'a> {
+    scheme: &'a str,
+    /// Everything between `://` and the port, e.g. `user@[::1]`
+    authority: &'a str,
+    port: Option<u16>,
+    /// The path, query and fragment
+    tail: &'a str,
+}
+
+impl<'a> UrlParts<'a> {
+    fn parse(url: &'a str) -> Result<Self, &'static str> {
+        if url.chars().any(char::is_whitespace) {
+            return Err("contains whitespace");
+        }
+
+        let (scheme, rest) = url.split_once("://").ok_or("has no scheme")?;
+        let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let (authority, tail) = rest.split_at(authority_end);
+
+        let host_start = authority.rfind('@').map_or(0, |index| index + 1);
+        let host_and_port = &authority[host_start..];
+        let (host, port) = match host_and_port.strip_prefix('[') {
+            // IPv6 literal, e.g. `[::1]:8899`
+            Some(literal) => match literal.split_once(']') {
+                Some((host, port)) => (host, port.strip_prefix(':')),
+                None => return Err("has an unterminated IPv6 address"),
+            },
+            None => match host_and_port.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host_and_port, None),
+            },
+        };
+
+        if host.is_empty() {
+            return Err("has no host");
+        }
+
+        match port {
+            Some(port) => Ok(UrlParts {
+                scheme,
+                authority: &authority[..authority.len() - port.len() - 1],
+                port: Some(port.parse().map_err(|_| "has an invalid port")?),
+                tail,
+            }),
+            None => Ok(UrlParts {
+                scheme,
+                authority,
+                port: None,
+                tail,
+            }),
         }
     }
 }
 
-impl Default for Cluster {
-    fn default() -> Self {
-        Cluster::Devnet
+/// Checks that `url` has one of `schemes` and a host, with a valid port if
+/// any
+fn validate_url(url: &str, schemes: &[&str]) -> PoseidonResult<()> {
+    let invalid = |reason: &str| PoseidonError::InvalidCluster(format!("`{}` {}", url, reason));
+
+    let parts = UrlParts::parse(url).map_err(invalid)?;
+    if !schemes
+        .iter()
+        .any(|expected| expected.eq_ignore_ascii_case(parts.scheme))
+    {
+        return Err(invalid(&format!("must use one of {}", schemes.join(", "))));
+    }
+
+    Ok(())
+}
+
+/// The pubsub URL served next to an RPC URL, following the Solana CLI:
+/// `http` becomes `ws`, `https` becomes `wss` and an explicit port is
+/// incremented, the path and query are kept
+fn derive_ws_url(rpc_url: &str) -> String {
+    let parts = match UrlParts::parse(rpc_url) {
+        Ok(parts) => parts,
+        // Left for the websocket client to report
+        Err(_) => return rpc_url.to_owned(),
+    };
+
+    let scheme = if parts.scheme.eq_ignore_ascii_case("https") {
+        "wss"
+    } else if parts.scheme.eq_ignore_ascii_case("http") {
+        "ws"
+    } else {
+        parts.scheme
+    };
+
+    match parts.port {
+        Some(port) => format!(
+            "{}://{}:{}{}",
+            scheme,
+            parts.authority,
+            port.saturating_add(1),
+            parts.tail
+        ),
+        None => format!("{}://{}{}", scheme, parts.authority, parts.tail),
+    }
+}
+
+/// How settled the state a node answers with must be, from the most recent
+/// to the safest
+#[derive(
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    /// The most recent block processed by the node, it may be skipped
+    Processed,
+    /// Voted on by a supermajority of the cluster
+    #[default]
+    Confirmed,
+    /// Rooted by a supermajority of the cluster
+    Finalized,
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Commitment::Processed => f.write_str("processed"),
+            Commitment::Confirmed => f.write_str("confirmed"),
+            Commitment::Finalized => f.write_str("finalized"),
+        }
+    }
+}
+
+/// Also accepts the deprecated levels still found in Solana CLI configs
+impl FromStr for Commitment {
+    type Err = PoseidonError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "processed" | "recent" => Ok(Commitment::Processed),
+            "confirmed" | "single" | "singleGossip" => Ok(Commitment::Confirmed),
+            "finalized" | "root" | "max" => Ok(Commitment::Finalized),
+            commitment => Err(PoseidonError::InvalidConfig(format!(
+                "unknown commitment `{}`",
+                commitment
+            ))),
+        }
     }
 }
 
@@ -0,0 +1,150 @@
+use crate::{borsh_from_slice, PoseidonError, PoseidonResult, UserData};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+/// The default ceiling on decompressed payloads, 256 MiB
+pub const DEFAULT_MAX_DECODED_LEN: u64 = 256 * 1024 * 1024;
+
+/// How the bytes of an [EncodedUserData] are encoded
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub enum UserDataEncoding {
+    /// The bytes are stored as is
+    Identity,
+    /// The bytes are compressed with raw DEFLATE (RFC 1951).
+    /// Requires the `compression` feature.
+    Deflate,
+}
+
+/// A [UserData] whose payload may be compressed. The encoding is the first
+/// field so decoders can pick the right codec before touching the payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct EncodedUserData {
+    pub encoding: UserDataEncoding,
+    pub mime: String,
+    /// Length of the payload once decoded
+    pub decoded_len: u64,
+    pub data: Vec<u8>,
+}
+
+impl UserData {
+    pub fn encode(&self, encoding: UserDataEncoding) -> PoseidonResult<EncodedUserData> {
+        let data = match encoding {
+            UserDataEncoding::Identity => self.data.clone(),
+            UserDataEncoding::Deflate => deflate(&self.data)?,
+        };
+
+        Ok(EncodedUserData {
+            encoding,
+            mime: self.mime.clone(),
+            decoded_len: self.data.len() as u64,
+            data,
+        })
+    }
+
+    /// Compresses the payload, keeping it uncompressed if compression does
+    /// not make it smaller
+    #[cfg(feature = "compression")]
+    pub fn compress(&self) -> PoseidonResult<EncodedUserData> {
+        let compressed = self.encode(UserDataEncoding::Deflate)?;
+
+        if compressed.data.len() < self.data.len() {
+            Ok(compressed)
+        } else {
+            self.encode(UserDataEncoding::Identity)
+        }
+    }
+
+    /// Borsh deserializes an [EncodedUserData] and decodes it, refusing
+    /// payloads that decode to more than `max_decoded_len` bytes
+    pub fn from_encoded_bytes(bytes: &[u8], max_decoded_len: u64) -> PoseidonResult<UserData> {
+        borsh_from_slice::<EncodedUserData>(bytes)?.decode(max_decoded_len)
+    }
+}
+
+impl EncodedUserData {
+    /// Decodes the payload, refusing payloads that decode to more than
+    /// `max_decoded_len` bytes so that decompression bombs are cut off early
+    pub fn decode(self, max_decoded_len: u64) -> PoseidonResult<UserData> {
+        if self.decoded_len > max_decoded_len {
+            return Err(PoseidonError::DecodedSizeLimitExceeded {
+                limit: max_decoded_len,
+            });
+        }
+
+        // Inflating stops one byte past the declared length, which has already
+        // been checked against the ceiling, so a lying header is caught below
+        let data = match self.encoding {
+            UserDataEncoding::Identity => self.data,
+            UserDataEncoding::Deflate => inflate(&self.data, self.decoded_len)?,
+        };
+
+        if data.len() as u64 != self.decoded_len {
+            return Err(PoseidonError::InvalidEncodedData(format!(
+                "expected `{}` decoded bytes, found `{}`",
+                self.decoded_len,
+                data.len()
+            )));
+        }
+
+        Ok(UserData {
+            mime: self.mime,
+            data,
+        })
+    }
+
+    pub fn to_bytes(&self) -> PoseidonResult<Vec<u8>> {
+        Ok(self.try_to_vec()?)
+    }
+}
+
+#[cfg(feature = "compression")]
+fn deflate(data: &[u8]) -> PoseidonResult<Vec<u8>> {
+    use flate2::{write::DeflateEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+
+    Ok(encoder.finish()?)
+}
+
+#[cfg(feature = "compression")]
+fn inflate(data: &[u8], decoded_len: u64) -> PoseidonResult<Vec<u8>> {
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    let mut decoded = Vec::new();
+    DeflateDecoder::new(data)
+        .take(decoded_len.saturating_add(1))
+        .read_to_end(&mut decoded)
+        .map_err(|error| PoseidonError::InvalidEncodedData(error.to_string()))?;
+
+    Ok(decoded)
+}
+
+#[cfg(not(feature = "compression"))]
+fn deflate(_data: &[u8]) -> PoseidonResult<Vec<u8>> {
+    Err(PoseidonError::UnsupportedEncoding(
+        "`Deflate` requires the `compression` feature".to_owned(),
+    ))
+}
+
+#[cfg(not(feature = "compression"))]
+fn inflate(_data: &[u8], _decoded_len: u64) -> PoseidonResult<Vec<u8>> {
+    Err(PoseidonError::UnsupportedEncoding(
+        "`Deflate` requires the `compression` feature".to_owned(),
+    ))
+}
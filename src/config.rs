@@ -0,0 +1,220 @@
+use crate::{
+    default_keypair_path, expand_home_path, resolve_keypair_path, Cluster, Commitment,
+    PoseidonError, PoseidonIoError, PoseidonResult,
+};
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The Poseidon configuration, relative to the home directory
+pub const DEFAULT_CONFIG_PATH: &str = ".config/poseidon/config.toml";
+
+/// The Solana CLI configuration, relative to the home directory. It is used
+/// when there is no Poseidon configuration.
+pub const SOLANA_CLI_CONFIG_PATH: &str = ".config/solana/cli/config.yml";
+
+/// Path of the configuration file to load instead of the default one
+pub const ENV_CONFIG: &str = "POSEIDON_CONFIG";
+/// Cluster moniker or RPC URL, replaces the cluster of the file
+pub const ENV_CLUSTER: &str = "POSEIDON_CLUSTER";
+/// RPC URL, takes precedence over [ENV_CLUSTER]
+pub const ENV_RPC_URL: &str = "POSEIDON_RPC_URL";
+/// Websocket URL, replaces the pubsub endpoint of the cluster
+pub const ENV_WS_URL: &str = "POSEIDON_WS_URL";
+/// Keypair path, replaces the keypair of the file
+pub const ENV_KEYPAIR: &str = "POSEIDON_KEYPAIR";
+/// Commitment level, replaces the commitment of the file
+pub const ENV_COMMITMENT: &str = "POSEIDON_COMMITMENT";
+
+/// The layout of a configuration file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    /// The `config.yml` written by `solana config set`
+    SolanaCli,
+}
+
+impl ConfigFormat {
+    /// The format of a file from its extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> PoseidonResult<ConfigFormat> {
+        let path = path.as_ref();
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(ConfigFormat::Toml),
+            Some("json") => Ok(ConfigFormat::Json),
+            Some("yml" | "yaml") => Ok(ConfigFormat::SolanaCli),
+            _ => Err(PoseidonError::InvalidConfig(format!(
+                "`{}` is not a .toml, .json or .yml file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// The settings every Poseidon binary starts from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoseidonConfig {
+    pub cluster: Cluster,
+    pub keypair_path: PathBuf,
+    pub commitment: Commitment,
+}
+
+/// The settings as written in a file or the environment, before they are
+/// validated
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    /// Moniker or RPC URL
+    cluster: Option<String>,
+    rpc_url: Option<String>,
+    ws_url: Option<String>,
+    keypair_path: Option<String>,
+    commitment: Option<String>,
+}
+
+/// The subset of the Solana CLI configuration that Poseidon uses
+#[derive(Debug, Deserialize)]
+struct SolanaCliConfig {
+    json_rpc_url: Option<String>,
+    websocket_url: Option<String>,
+    keypair_path: Option<String>,
+    commitment: Option<String>,
+}
+
+impl PoseidonConfig {
+    /// Loads the configuration from the file named by `POSEIDON_CONFIG`, or
+    /// else [DEFAULT_CONFIG_PATH], or else [SOLANA_CLI_CONFIG_PATH], and
+    /// applies the `POSEIDON_*` environment overrides. Without any file the
+    /// defaults are devnet, the Solana CLI keypair and `confirmed`.
+    pub fn load() -> PoseidonResult<PoseidonConfig> {
+        PoseidonConfig::load_with_env(|name| std::env::var(name).ok())
+    }
+
+    /// [PoseidonConfig::load] with the environment variables looked up by
+    /// `env`
+    pub fn load_with_env<F>(env: F) -> PoseidonResult<PoseidonConfig>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let raw = match env(ENV_CONFIG).filter(|path| !path.is_empty()) {
+            Some(path) => read_raw_config(&expand_home_path(path)?)?,
+            None => {
+                let home = dirs::home_dir().ok_or(PoseidonError::HomeDirectoryNotFound)?;
+
+                [DEFAULT_CONFIG_PATH, SOLANA_CLI_CONFIG_PATH]
+                    .iter()
+                    .map(|path| home.join(path))
+                    .find(|path| path.is_file())
+                    .map_or(Ok(RawConfig::default()), |path| read_raw_config(&path))?
+            }
+        };
+
+        raw.with_env(env).resolve()
+    }
+
+    /// Reads a configuration file, in the format given by its extension,
+    /// without environment overrides
+    pub fn from_file<P: AsRef<Path>>(path: P) -> PoseidonResult<PoseidonConfig> {
+        read_raw_config(path.as_ref())?.resolve()
+    }
+
+    pub fn parse(contents: &str, format: ConfigFormat) -> PoseidonResult<PoseidonConfig> {
+        RawConfig::parse(contents, format)?.resolve()
+    }
+}
+
+fn read_raw_config(path: &Path) -> PoseidonResult<RawConfig> {
+    let format = ConfigFormat::from_path(path)?;
+    let contents =
+        fs::read_to_string(path).map_err(|error| PoseidonIoError::from(error).with_path(path))?;
+
+    RawConfig::parse(&contents, format).map_err(|error| error.context(path.display().to_string()))
+}
+
+impl RawConfig {
+    fn parse(contents: &str, format: ConfigFormat) -> PoseidonResult<RawConfig> {
+        let invalid =
+            |error: &dyn std::fmt::Display| PoseidonError::InvalidConfig(error.to_string());
+
+        match format {
+            ConfigFormat::Toml => toml::from_str(contents).map_err(|error| invalid(&error)),
+            ConfigFormat::Json => serde_json::from_str(contents).map_err(|error| invalid(&error)),
+            ConfigFormat::SolanaCli => {
+                let config: SolanaCliConfig =
+                    serde_yaml::from_str(contents).map_err(|error| invalid(&error))?;
+
+                Ok(RawConfig {
+                    cluster: None,
+                    rpc_url: config.json_rpc_url,
+                    ws_url: config.websocket_url,
+                    keypair_path: config.keypair_path,
+                    commitment: config.commitment,
+                })
+            }
+        }
+    }
+
+    fn with_env<F>(mut self, env: F) -> RawConfig
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let env = |name| env(name).filter(|value: &String| !value.is_empty());
+
+        if let Some(cluster) = env(ENV_CLUSTER) {
+            self.cluster = Some(cluster);
+            self.rpc_url = None;
+            self.ws_url = None;
+        }
+        if let Some(rpc_url) = env(ENV_RPC_URL) {
+            self.rpc_url = Some(rpc_url);
+        }
+        if let Some(ws_url) = env(ENV_WS_URL) {
+            self.ws_url = Some(ws_url);
+        }
+        if let Some(keypair_path) = env(ENV_KEYPAIR) {
+            self.keypair_path = Some(keypair_path);
+        }
+        if let Some(commitment) = env(ENV_COMMITMENT) {
+            self.commitment = Some(commitment);
+        }
+
+        self
+    }
+
+    fn resolve(self) -> PoseidonResult<PoseidonConfig> {
+        // The Solana CLI writes an empty websocket URL when it is derived
+        let ws_url = self.ws_url.filter(|ws_url| !ws_url.is_empty());
+
+        let cluster = match (self.rpc_url, self.cluster) {
+            (Some(rpc_url), _) => Cluster::from_url(&rpc_url)?,
+            (None, Some(cluster)) => cluster.parse()?,
+            (None, None) => Cluster::default(),
+        };
+        let cluster = match ws_url {
+            // A built-in cluster behind a custom pubsub endpoint
+            Some(ws_url) if ws_url != cluster.ws_url() => {
+                Cluster::custom(cluster.url(), Some(ws_url))?
+            }
+            _ => cluster,
+        };
+
+        let keypair_path = match self.keypair_path {
+            Some(keypair_path) => resolve_keypair_path(keypair_path)?,
+            None => default_keypair_path()?,
+        };
+
+        let commitment = match self.commitment {
+            Some(commitment) => commitment.parse()?,
+            None => Commitment::default(),
+        };
+
+        Ok(PoseidonConfig {
+            cluster,
+            keypair_path,
+            commitment,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{borsh_from_slice, Base58Sha256Hash, DataID, PoseidonError, PoseidonResult, UserData};
+use borsh::BorshSerialize;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+impl UserData {
+    /// The content address of the data, the SHA-256 hash of its borsh encoding.
+    ///
+    /// Borsh encodes `mime` and `data` as little endian `u32` length prefixed
+    /// byte strings, so the encoding, and therefore the ID, is canonical.
+    pub fn data_id(&self) -> PoseidonResult<DataID> {
+        let mut hasher = Sha256::new();
+        self.serialize(&mut hasher)?;
+
+        Ok(hasher.finalize().into())
+    }
+
+    /// Checks that the data hashes to `data_id`
+    pub fn verify_data_id(&self, data_id: &DataID) -> PoseidonResult<()> {
+        check_data_id(&self.data_id()?, data_id)
+    }
+}
+
+/// Computes the [DataID] of a borsh encoded [UserData] by hashing the blob
+/// as is. The blob is decoded first to check that it is a [UserData] with no
+/// trailing bytes.
+pub fn data_id_from_borsh(blob: &[u8]) -> PoseidonResult<DataID> {
+    borsh_from_slice::<UserData>(blob)?;
+
+    Ok(Sha256::digest(blob).into())
+}
+
+/// Checks that a borsh encoded [UserData] hashes to `data_id`
+pub fn verify_data_id(blob: &[u8], data_id: &DataID) -> PoseidonResult<()> {
+    check_data_id(&data_id_from_borsh(blob)?, data_id)
+}
+
+pub fn data_id_to_base58(data_id: &DataID) -> Base58Sha256Hash {
+    bs58::encode(data_id).into_string()
+}
+
+pub fn data_id_from_base58(value: &str) -> PoseidonResult<DataID> {
+    let decoded = bs58::decode(value)
+        .into_vec()
+        .map_err(|_| PoseidonError::InvalidBase58Sha256Hash)?;
+
+    decoded
+        .as_slice()
+        .try_into()
+        .map_err(|_| PoseidonError::InvalidBase58Sha256Hash)
+}
+
+fn check_data_id(computed: &DataID, expected: &DataID) -> PoseidonResult<()> {
+    if bool::from(computed.ct_eq(expected)) {
+        Ok(())
+    } else {
+        Err(PoseidonError::DataIdMismatch)
+    }
+}
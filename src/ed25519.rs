@@ -0,0 +1,333 @@
+use crate::{Base58PublicKey, Base58Signature, PoseidonError, PoseidonResult};
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+/// An Ed25519 public key, displayed and parsed as a base58 string
+#[derive(Clone, Copy, BorshSerialize, BorshDeserialize)]
+pub struct Ed25519PublicKey([u8; 32]);
+
+/// An Ed25519 signature, displayed and parsed as a base58 string
+#[derive(Clone, Copy, BorshSerialize, BorshDeserialize)]
+pub struct Ed25519Signature([u8; 64]);
+
+macro_rules! impl_ed25519_bytes {
+    ($name:ident, $base58:ident, $len:literal, $error:expr, $expecting:literal) => {
+        impl $name {
+            pub const LENGTH: usize = $len;
+
+            pub const fn new(bytes: [u8; $len]) -> Self {
+                $name(bytes)
+            }
+
+            pub fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            pub fn to_bytes(&self) -> [u8; $len] {
+                self.0
+            }
+
+            pub fn to_base58(&self) -> $base58 {
+                bs58::encode(&self.0).into_string()
+            }
+
+            pub fn from_base58(value: &str) -> PoseidonResult<Self> {
+                let decoded = bs58::decode(value).into_vec().map_err(|_| $error)?;
+
+                Self::try_from(decoded.as_slice())
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                $name(bytes)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = PoseidonError;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+                let bytes: [u8; $len] = bytes.try_into().map_err(|_| $error)?;
+
+                Ok($name(bytes))
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = PoseidonError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                Self::from_base58(value)
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        /// Compares in constant time so that equality checks on keys and
+        /// signatures do not leak timing information.
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.ct_eq(&other.0).into()
+            }
+        }
+
+        impl Eq for $name {}
+
+        /// Orders by the raw bytes, as the `[u8; N]` aliases these replace
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.to_base58())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self.to_base58())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&self.to_base58())
+                } else {
+                    serializer.serialize_bytes(&self.0)
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct BytesVisitor;
+
+                impl<'de> de::Visitor<'de> for BytesVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str($expecting)
+                    }
+
+                    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                        $name::from_base58(value).map_err(|_| {
+                            de::Error::invalid_value(de::Unexpected::Str(value), &self)
+                        })
+                    }
+
+                    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+                        $name::try_from(value)
+                            .map_err(|_| de::Error::invalid_length(value.len(), &self))
+                    }
+
+                    fn visit_seq<A: de::SeqAccess<'de>>(
+                        self,
+                        mut seq: A,
+                    ) -> Result<Self::Value, A::Error> {
+                        let mut bytes = [0u8; $len];
+                        for (index, byte) in bytes.iter_mut().enumerate() {
+                            *byte = seq
+                                .next_element()?
+                                .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+                        }
+                        if seq.next_element::<u8>()?.is_some() {
+                            return Err(de::Error::invalid_length($len + 1, &self));
+                        }
+
+                        Ok($name(bytes))
+                    }
+                }
+
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(BytesVisitor)
+                } else {
+                    deserializer.deserialize_bytes(BytesVisitor)
+                }
+            }
+        }
+    };
+}
+
+impl_ed25519_bytes!(
+    Ed25519PublicKey,
+    Base58PublicKey,
+    32,
+    PoseidonError::InvalidBase58Ed25519PublicKey,
+    "a base58 encoded or 32 byte Ed25519 public key"
+);
+
+impl_ed25519_bytes!(
+    Ed25519Signature,
+    Base58Signature,
+    64,
+    PoseidonError::InvalidBase58Ed25519Signature,
+    "a base58 encoded or 64 byte Ed25519 signature"
+);
+
+impl Ed25519PublicKey {
+    /// The X25519 public key birationally equivalent to this Ed25519 public key
+    #[cfg(feature = "sealed_box")]
+    pub(crate) fn to_x25519(self) -> PoseidonResult<[u8; 32]> {
+        VerifyingKey::from_bytes(&self.0)
+            .map(|verifying_key| verifying_key.to_montgomery().to_bytes())
+            .map_err(|_| PoseidonError::InvalidEd25519PublicKey)
+    }
+
+    /// Verifies `signature` over `message` using strict verification, which
+    /// rejects small order keys and malleable signatures.
+    pub fn verify(&self, message: &[u8], signature: &Ed25519Signature) -> PoseidonResult<()> {
+        let verifying_key = VerifyingKey::from_bytes(&self.0)
+            .map_err(|_| PoseidonError::SignatureVerificationFailed)?;
+        let signature = ed25519_dalek::Signature::from_bytes(&signature.0);
+
+        verifying_key
+            .verify_strict(message, &signature)
+            .map_err(|_| PoseidonError::SignatureVerificationFailed)
+    }
+}
+
+/// An Ed25519 keypair. The secret key is zeroized when the keypair is dropped.
+///
+/// The byte and base58 forms are the 64 byte `secret || public` layout used by
+/// the Solana CLI.
+pub struct Ed25519Keypair(SigningKey);
+
+impl Ed25519Keypair {
+    pub const LENGTH: usize = 64;
+    pub const SEED_LENGTH: usize = 32;
+
+    /// Generates a new keypair from the operating system's random number generator
+    pub fn generate() -> Self {
+        Ed25519Keypair(SigningKey::generate(&mut OsRng))
+    }
+
+    /// Derives the keypair from a 32 byte secret seed
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Ed25519Keypair(SigningKey::from_bytes(seed))
+    }
+
+    /// Parses the 64 byte `secret || public` layout, checking that the public
+    /// half matches the secret half.
+    pub fn from_bytes(bytes: &[u8]) -> PoseidonResult<Self> {
+        let bytes: Zeroizing<[u8; 64]> = Zeroizing::new(
+            bytes
+                .try_into()
+                .map_err(|_| PoseidonError::InvalidBase58Ed25519SecretKey)?,
+        );
+
+        SigningKey::from_keypair_bytes(&bytes)
+            .map(Ed25519Keypair)
+            .map_err(|_| PoseidonError::InvalidBase58Ed25519SecretKey)
+    }
+
+    pub fn from_base58(value: &str) -> PoseidonResult<Self> {
+        let decoded = Zeroizing::new(
+            bs58::decode(value)
+                .into_vec()
+                .map_err(|_| PoseidonError::InvalidBase58Ed25519SecretKey)?,
+        );
+
+        Self::from_bytes(&decoded)
+    }
+
+    pub fn to_bytes(&self) -> Zeroizing<[u8; 64]> {
+        Zeroizing::new(self.0.to_keypair_bytes())
+    }
+
+    pub fn to_base58(&self) -> Zeroizing<String> {
+        Zeroizing::new(bs58::encode(self.to_bytes().as_ref()).into_string())
+    }
+
+    /// The 32 byte secret seed the keypair was derived from
+    pub fn seed(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.0.to_bytes())
+    }
+
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        Ed25519PublicKey(self.0.verifying_key().to_bytes())
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Ed25519Signature {
+        Ed25519Signature(self.0.sign(message).to_bytes())
+    }
+
+    pub fn verify(&self, message: &[u8], signature: &Ed25519Signature) -> PoseidonResult<()> {
+        self.public_key().verify(message, signature)
+    }
+
+    /// The X25519 secret scalar birationally equivalent to this signing key
+    #[cfg(feature = "sealed_box")]
+    pub(crate) fn to_x25519_secret(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.0.to_scalar_bytes())
+    }
+}
+
+impl FromStr for Ed25519Keypair {
+    type Err = PoseidonError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::from_base58(value)
+    }
+}
+
+impl TryFrom<&[u8]> for Ed25519Keypair {
+    type Error = PoseidonError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl PartialEq for Ed25519Keypair {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes().ct_eq(other.to_bytes().as_ref()).into()
+    }
+}
+
+impl Eq for Ed25519Keypair {}
+
+/// Only the public key is printed so secrets never end up in logs
+impl fmt::Debug for Ed25519Keypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ed25519Keypair")
+            .field(&self.public_key())
+            .finish()
+    }
+}
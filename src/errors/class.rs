@@ -0,0 +1,234 @@
+use crate::{HttpError, JsonError, PoseidonError, PoseidonErrorKind, TransactionError};
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// The longest delay suggested by [PoseidonError::backoff]
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How an error should be handled by the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ErrorClass {
+    /// The same request may succeed if it is retried later
+    Transient,
+    /// The request will keep failing no matter how often it is retried
+    Permanent,
+    /// The request will keep failing until the caller fixes its input
+    User,
+}
+
+impl ErrorClass {
+    pub fn is_retryable(&self) -> bool {
+        *self == ErrorClass::Transient
+    }
+
+    /// Both [ErrorClass::Permanent] and [ErrorClass::User] errors are
+    /// permanent
+    pub fn is_permanent(&self) -> bool {
+        !self.is_retryable()
+    }
+}
+
+impl PoseidonError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            PoseidonError::Context { source, .. } => source.class(),
+            PoseidonError::IoErr(error) => error.kind.class(),
+            PoseidonError::Tx(error) => error.class(),
+            PoseidonError::Http(error) => error.class(),
+            PoseidonError::Json(error) => error.class(),
+            // The pool recovers once its endpoints are probed again
+            PoseidonError::NoHealthyEndpoint => ErrorClass::Transient,
+            // Nodes recover once they catch up with the cluster
+            PoseidonError::NodeUnhealthy(..) => ErrorClass::Transient,
+            PoseidonError::MissingEd25519PublicKey
+            | PoseidonError::MissingKeypair
+            | PoseidonError::MissingTxSignature
+            | PoseidonError::PathIsNotValidUtf8
+            | PoseidonError::InvalidUtf8(..)
+            | PoseidonError::InvalidBase58Ed25519SecretKey
+            | PoseidonError::InvalidBase58Ed25519PublicKey
+            | PoseidonError::InvalidBase58Ed25519Signature
+            | PoseidonError::InvalidBase58Sha256Hash
+            | PoseidonError::InvalidEd25519PublicKey
+            | PoseidonError::MnemonicInvalidWordCount(..)
+            | PoseidonError::MnemonicUnknownWord { .. }
+            | PoseidonError::MnemonicInvalidChecksum
+            | PoseidonError::InvalidDerivationPath(..)
+            | PoseidonError::KeystoreWrongPassword
+            | PoseidonError::InvalidMimeType(..)
+            | PoseidonError::MimeTypeMismatch { .. }
+            | PoseidonError::DecodedSizeLimitExceeded { .. }
+            | PoseidonError::InvalidByteToUtf8StringConversion
+            | PoseidonError::InvalidEd25519PublicKeyHex
+            | PoseidonError::InvalidHexCharacter { .. }
+            | PoseidonError::OddLength
+            | PoseidonError::InvalidStringLength
+            | PoseidonError::InvalidUtf8Sequence { .. }
+            | PoseidonError::InvalidBase58Character { .. }
+            | PoseidonError::NonAsciiBase58Character { .. }
+            | PoseidonError::InvalidBase58Length
+            | PoseidonError::InvalidSliceLength
+            | PoseidonError::InvalidCluster(..)
+            | PoseidonError::InvalidConfig(..) => ErrorClass::User,
+            _ => ErrorClass::Permanent,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    pub fn is_permanent(&self) -> bool {
+        self.class().is_permanent()
+    }
+
+    /// The suggested delay before the first retry, `None` if the error is
+    /// not retryable
+    pub fn retry_after(&self) -> Option<Duration> {
+        if !self.is_retryable() {
+            return None;
+        }
+
+        let delay = match self.root_cause() {
+            PoseidonError::Tx(error) => error.retry_after(),
+            _ => None,
+        };
+
+        Some(delay.unwrap_or(Duration::from_millis(500)))
+    }
+
+    /// The suggested delay before retry number `attempt`, starting at zero.
+    /// The delay from [PoseidonError::retry_after] doubles on every attempt
+    /// up to [MAX_BACKOFF].
+    pub fn backoff(&self, attempt: u32) -> Option<Duration> {
+        let initial = self.retry_after()?;
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+
+        Some(initial.saturating_mul(factor).min(MAX_BACKOFF))
+    }
+}
+
+impl PoseidonErrorKind {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            PoseidonErrorKind::ConnectionRefused
+            | PoseidonErrorKind::ConnectionReset
+            | PoseidonErrorKind::ConnectionAborted
+            | PoseidonErrorKind::NotConnected
+            | PoseidonErrorKind::AddrNotAvailable
+            | PoseidonErrorKind::WouldBlock
+            | PoseidonErrorKind::BrokenPipe
+            | PoseidonErrorKind::TimedOut
+            | PoseidonErrorKind::Interrupted
+            | PoseidonErrorKind::UnexpectedEof
+            | PoseidonErrorKind::HostUnreachable
+            | PoseidonErrorKind::NetworkUnreachable
+            | PoseidonErrorKind::NetworkDown
+            | PoseidonErrorKind::StaleNetworkFileHandle
+            | PoseidonErrorKind::ResourceBusy
+            | PoseidonErrorKind::ExecutableFileBusy
+            | PoseidonErrorKind::Deadlock => ErrorClass::Transient,
+            PoseidonErrorKind::NotFound
+            | PoseidonErrorKind::PermissionDenied
+            | PoseidonErrorKind::AlreadyExists
+            | PoseidonErrorKind::InvalidInput
+            | PoseidonErrorKind::NotADirectory
+            | PoseidonErrorKind::IsADirectory
+            | PoseidonErrorKind::DirectoryNotEmpty
+            | PoseidonErrorKind::InvalidFilename
+            | PoseidonErrorKind::ArgumentListTooLong => ErrorClass::User,
+            _ => ErrorClass::Permanent,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+}
+
+impl TransactionError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            TransactionError::AccountInUse
+            | TransactionError::BlockhashNotFound
+            | TransactionError::ClusterMaintenance
+            | TransactionError::WouldExceedMaxBlockCostLimit
+            | TransactionError::WouldExceedMaxAccountCostLimit
+            | TransactionError::WouldExceedAccountDataBlockLimit
+            | TransactionError::WouldExceedMaxVoteCostLimit
+            | TransactionError::ProgramExecutionTemporarilyRestricted { .. }
+            | TransactionError::ProgramCacheHitMaxLimit => ErrorClass::Transient,
+            TransactionError::AccountNotFound
+            | TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. }
+            | TransactionError::InvalidAccountForFee
+            | TransactionError::MissingSignatureForFee
+            | TransactionError::SignatureFailure => ErrorClass::User,
+            _ => ErrorClass::Permanent,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// The suggested delay before the first retry, `None` if the error is
+    /// not retryable
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            // Cluster maintenance lasts minutes, not slots
+            TransactionError::ClusterMaintenance => Some(Duration::from_secs(30)),
+            // A fresh blockhash, an unlocked account or room in the next
+            // block are all a slot away
+            error if error.is_retryable() => Some(Duration::from_millis(400)),
+            _ => None,
+        }
+    }
+}
+
+impl HttpError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            // A truncated body or a flaky resolver usually go away on retry
+            HttpError::MalformedChunkLength
+            | HttpError::MalformedChunkEnd
+            | HttpError::MalformedContentLength
+            | HttpError::AddressNotFound
+            | HttpError::ProxyConnect => ErrorClass::Transient,
+            HttpError::BadProxy | HttpError::BadProxyCreds | HttpError::InvalidProxyCreds => {
+                ErrorClass::User
+            }
+            _ => ErrorClass::Permanent,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+}
+
+impl JsonError {
+    /// Internal errors and the Solana node errors for unhealthy nodes or data
+    /// that is not available yet are transient
+    pub fn class(&self) -> ErrorClass {
+        match self.code {
+            // Internal error
+            -32603
+            // Block not available
+            | -32004
+            // Node unhealthy
+            | -32005
+            // Slot status not available
+            | -32014
+            // Minimum context slot not reached
+            | -32016 => ErrorClass::Transient,
+            // Invalid request, method not found, invalid params
+            -32602..=-32600 => ErrorClass::User,
+            _ => ErrorClass::Permanent,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+}
@@ -1,18 +1,13 @@
-use crate::{PoseidonErrorKind, StoreErr, TransactionError};
-use borsh::{BorshDeserialize, BorshSerialize};
+use crate::{HttpError, JsonError, PoseidonIoError, RustlsError, StoreErr, TransactionError};
+use borsh::BorshDeserialize;
+use core::fmt;
 use serde::{Deserialize, Serialize};
 
-#[cfg(feature = "http")]
-use crate::{HttpError, JsonError};
-
-#[cfg(feature = "rustls")]
-use crate::RustlsError;
-
 pub type PoseidonResult<T> = Result<T, PoseidonError>;
 
-#[derive(
-    Debug, PartialEq, PartialOrd, Eq, Ord, Deserialize, Serialize, BorshDeserialize, BorshSerialize,
-)]
+/// Borsh encodes as a stable numeric code rather than the variant index,
+/// see [PoseidonError::code].
+#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Deserialize, Serialize)]
 pub enum PoseidonError {
     MissingEd25519PublicKey,
     MissingKeypair,
@@ -25,7 +20,7 @@ pub enum PoseidonError {
     InvalidBase58Ed25519Signature,
     RepoCreatePermissionDenied,
     RepoAlreadyExists,
-    IoErr(PoseidonErrorKind),
+    IoErr(PoseidonIoError),
     InvalidByteToUtf8StringConversion,
     InvalidEd25519PublicKeyHex,
     /// An invalid character was found. Valid ones are: `0...9`, `a...f`
@@ -53,16 +48,314 @@ pub enum PoseidonError {
     AccountNotFound,
     UnableToDeserializeAccountInfo,
     UnableToSerializeTx,
-    #[cfg(feature = "rustls")]
     Rustls(RustlsError),
     Tx(TransactionError),
-    #[cfg(feature = "http")]
     Http(HttpError),
-    #[cfg(feature = "http")]
     Json(JsonError),
     SerdeJson(String),
     Store(StoreErr),
     Unspecified(String),
+    /// The signature does not match the message and public key
+    SignatureVerificationFailed,
+    /// The mnemonic does not have 12, 15, 18, 21 or 24 words
+    MnemonicInvalidWordCount(usize),
+    /// The word at `index` is not part of the BIP39 English wordlist
+    MnemonicUnknownWord {
+        index: usize,
+    },
+    /// The mnemonic checksum does not match its entropy
+    MnemonicInvalidChecksum,
+    /// The derivation path is malformed or contains a non-hardened index
+    InvalidDerivationPath(String),
+    /// The keystore password is incorrect
+    KeystoreWrongPassword,
+    /// The keystore file is malformed or has been tampered with
+    KeystoreCorrupted(String),
+    /// The keystore was written by a newer, unsupported format version
+    KeystoreUnsupportedVersion(u8),
+    /// The public key is not a valid point on the Ed25519 curve
+    InvalidEd25519PublicKey,
+    /// The encrypted data is truncated or was sealed with an unknown version
+    InvalidEncryptedData,
+    /// The encrypted data was not sealed for this keypair or has been modified
+    DecryptionFailed,
+    InvalidBase58Sha256Hash,
+    /// The data does not hash to the expected [crate::DataID]
+    DataIdMismatch,
+    /// The bytes are not a valid borsh encoding of the expected type
+    BorshDeserializationFailed(String),
+    /// The chunk manifest is inconsistent with itself
+    InvalidChunkManifest(String),
+    /// A chunk arrived out of sequence
+    ChunkOutOfOrder {
+        expected: u32,
+        found: u32,
+    },
+    /// A chunk does not match the length or hash recorded in the manifest
+    ChunkIntegrityMismatch {
+        index: u32,
+    },
+    /// The chunk stream ended before every chunk was produced or received
+    ChunkStreamIncomplete,
+    /// The mime type is not a valid RFC 6838 media type
+    InvalidMimeType(String),
+    /// The declared mime type does not match the detected content
+    MimeTypeMismatch {
+        declared: String,
+        detected: String,
+    },
+    /// The encoding is not supported by this build
+    UnsupportedEncoding(String),
+    /// The encoded payload is corrupt or does not match its declared length
+    InvalidEncodedData(String),
+    /// The payload decodes to more than the allowed number of bytes
+    DecodedSizeLimitExceeded {
+        limit: u64,
+    },
+    /// An error whose code this build does not know, either because it was
+    /// added in a newer version or because it belongs to a disabled feature
+    Unknown {
+        code: u32,
+        message: String,
+    },
+    /// An error annotated with what was being done when it happened, see
+    /// [crate::ResultExt]. `context` is ordered from the innermost to the
+    /// outermost operation.
+    Context {
+        context: Vec<String>,
+        source: Box<PoseidonError>,
+    },
+    /// The bytes are not valid UTF-8. `error_len` is `None` when the input
+    /// ends in the middle of a character.
+    InvalidUtf8Sequence {
+        valid_up_to: usize,
+        error_len: Option<u8>,
+    },
+    /// A character outside the base58 alphabet
+    InvalidBase58Character {
+        c: String,
+        index: usize,
+    },
+    /// A multi-byte character starting at byte `index` of a base58 string
+    NonAsciiBase58Character {
+        index: usize,
+    },
+    /// The base58 string decodes to more bytes than its container holds
+    InvalidBase58Length,
+    /// A slice was converted into an array of a different length
+    InvalidSliceLength,
+    /// A cluster moniker or endpoint URL that cannot be used
+    InvalidCluster(String),
+    /// Every endpoint of a [crate::ClusterPool] failed its last request or
+    /// health check
+    NoHealthyEndpoint,
+    /// A configuration file or environment variable that cannot be used
+    InvalidConfig(String),
+    /// An RPC node that answers `getHealth` with something other than `ok`
+    NodeUnhealthy(String),
+}
+
+impl fmt::Display for PoseidonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoseidonError::MissingEd25519PublicKey => f.write_str("missing Ed25519 public key"),
+            PoseidonError::MissingKeypair => f.write_str("missing keypair"),
+            PoseidonError::MissingTxSignature => f.write_str("missing transaction signature"),
+            PoseidonError::HomeDirectoryNotFound => {
+                f.write_str("the home directory of the current user could not be found")
+            }
+            PoseidonError::PathIsNotValidUtf8 => f.write_str("the path is not valid UTF-8"),
+            PoseidonError::InvalidUtf8(details) => write!(f, "invalid UTF-8: {}", details),
+            PoseidonError::InvalidBase58Ed25519SecretKey => {
+                f.write_str("invalid base58 encoded Ed25519 secret key")
+            }
+            PoseidonError::InvalidBase58Ed25519PublicKey => {
+                f.write_str("invalid base58 encoded Ed25519 public key")
+            }
+            PoseidonError::InvalidBase58Ed25519Signature => {
+                f.write_str("invalid base58 encoded Ed25519 signature")
+            }
+            PoseidonError::InvalidBase58Sha256Hash => {
+                f.write_str("invalid base58 encoded SHA-256 hash")
+            }
+            PoseidonError::InvalidEd25519PublicKey => {
+                f.write_str("the public key is not a valid Ed25519 curve point")
+            }
+            PoseidonError::SignatureVerificationFailed => {
+                f.write_str("signature verification failed")
+            }
+            PoseidonError::MnemonicInvalidWordCount(count) => write!(
+                f,
+                "the mnemonic has {} words, expected 12, 15, 18, 21 or 24",
+                count
+            ),
+            PoseidonError::MnemonicUnknownWord { index } => write!(
+                f,
+                "word {} of the mnemonic is not in the BIP39 English wordlist",
+                index
+            ),
+            PoseidonError::MnemonicInvalidChecksum => {
+                f.write_str("the mnemonic checksum is invalid")
+            }
+            PoseidonError::InvalidDerivationPath(details) => {
+                write!(f, "invalid derivation path: {}", details)
+            }
+            PoseidonError::KeystoreWrongPassword => f.write_str("wrong keystore password"),
+            PoseidonError::KeystoreCorrupted(details) => {
+                write!(f, "the keystore is corrupted: {}", details)
+            }
+            PoseidonError::KeystoreUnsupportedVersion(version) => {
+                write!(f, "unsupported keystore version {}", version)
+            }
+            PoseidonError::InvalidEncryptedData => {
+                f.write_str("the encrypted data is truncated or has an unknown version")
+            }
+            PoseidonError::DecryptionFailed => f.write_str("decryption failed"),
+            PoseidonError::DataIdMismatch => {
+                f.write_str("the data does not match the expected data ID")
+            }
+            PoseidonError::BorshDeserializationFailed(details) => {
+                write!(f, "borsh deserialization failed: {}", details)
+            }
+            PoseidonError::InvalidChunkManifest(details) => {
+                write!(f, "invalid chunk manifest: {}", details)
+            }
+            PoseidonError::ChunkOutOfOrder { expected, found } => write!(
+                f,
+                "expected chunk {} but received chunk {}",
+                expected, found
+            ),
+            PoseidonError::ChunkIntegrityMismatch { index } => write!(
+                f,
+                "chunk {} does not match the length or hash in the manifest",
+                index
+            ),
+            PoseidonError::ChunkStreamIncomplete => f.write_str("the chunk stream is incomplete"),
+            PoseidonError::InvalidMimeType(details) => {
+                write!(f, "invalid mime type: {}", details)
+            }
+            PoseidonError::MimeTypeMismatch { declared, detected } => write!(
+                f,
+                "the mime type is declared as `{}` but the content is `{}`",
+                declared, detected
+            ),
+            PoseidonError::UnsupportedEncoding(details) => {
+                write!(f, "unsupported encoding: {}", details)
+            }
+            PoseidonError::InvalidEncodedData(details) => {
+                write!(f, "invalid encoded data: {}", details)
+            }
+            PoseidonError::DecodedSizeLimitExceeded { limit } => write!(
+                f,
+                "the payload decodes to more than the limit of {} bytes",
+                limit
+            ),
+            PoseidonError::RepoCreatePermissionDenied => {
+                f.write_str("permission denied while creating the repository")
+            }
+            PoseidonError::RepoAlreadyExists => f.write_str("the repository already exists"),
+            PoseidonError::IoErr(error) => write!(f, "I/O error: {}", error),
+            PoseidonError::InvalidByteToUtf8StringConversion => {
+                f.write_str("the bytes are not a valid UTF-8 string")
+            }
+            PoseidonError::InvalidEd25519PublicKeyHex => {
+                f.write_str("invalid hex encoded Ed25519 public key")
+            }
+            PoseidonError::InvalidHexCharacter { c, index } => {
+                write!(f, "invalid hex character `{}` at index {}", c, index)
+            }
+            PoseidonError::OddLength => f.write_str("the hex string has an odd length"),
+            PoseidonError::InvalidStringLength => {
+                f.write_str("the hex string has the wrong length for its container")
+            }
+            PoseidonError::InvalidUtf8Sequence {
+                valid_up_to,
+                error_len: Some(error_len),
+            } => write!(
+                f,
+                "invalid UTF-8 sequence of {} bytes from index {}",
+                error_len, valid_up_to
+            ),
+            PoseidonError::InvalidUtf8Sequence {
+                valid_up_to,
+                error_len: None,
+            } => write!(
+                f,
+                "incomplete UTF-8 byte sequence from index {}",
+                valid_up_to
+            ),
+            PoseidonError::InvalidBase58Character { c, index } => {
+                write!(f, "invalid base58 character `{}` at index {}", c, index)
+            }
+            PoseidonError::NonAsciiBase58Character { index } => {
+                write!(
+                    f,
+                    "non-ASCII character at index {} of the base58 string",
+                    index
+                )
+            }
+            PoseidonError::InvalidBase58Length => {
+                f.write_str("the base58 string has the wrong length for its container")
+            }
+            PoseidonError::InvalidSliceLength => {
+                f.write_str("the slice has the wrong length for its array")
+            }
+            PoseidonError::SledCollectionNotFound(name) => {
+                write!(f, "the sled collection `{}` was not found", name)
+            }
+            PoseidonError::SledUnsupported(details) => {
+                write!(f, "unsupported sled operation: {}", details)
+            }
+            PoseidonError::SledReportableBug(details) => write!(f, "sled bug: {}", details),
+            PoseidonError::SledCorruption(details) => {
+                write!(f, "sled storage corruption at {}", details)
+            }
+            PoseidonError::AccountNotFound => f.write_str("account not found"),
+            PoseidonError::UnableToDeserializeAccountInfo => {
+                f.write_str("unable to deserialize the account info")
+            }
+            PoseidonError::UnableToSerializeTx => {
+                f.write_str("unable to serialize the transaction")
+            }
+            PoseidonError::Rustls(error) => write!(f, "TLS error: {}", error),
+            PoseidonError::Tx(error) => write!(f, "transaction error: {}", error),
+            PoseidonError::Http(error) => write!(f, "HTTP error: {}", error),
+            PoseidonError::Json(error) => write!(f, "JSON-RPC error: {}", error),
+            PoseidonError::InvalidCluster(details) => write!(f, "invalid cluster: {}", details),
+            PoseidonError::InvalidConfig(details) => {
+                write!(f, "invalid configuration: {}", details)
+            }
+            PoseidonError::NoHealthyEndpoint => f.write_str("no healthy RPC endpoint"),
+            PoseidonError::NodeUnhealthy(details) => write!(f, "unhealthy RPC node: {}", details),
+            PoseidonError::SerdeJson(details) => write!(f, "JSON error: {}", details),
+            PoseidonError::Store(error) => write!(f, "store error: {}", error),
+            PoseidonError::Unspecified(details) => f.write_str(details),
+            PoseidonError::Unknown { code, message } => {
+                write!(f, "error {}: {}", code, message)
+            }
+            PoseidonError::Context { context, source } => {
+                for message in context.iter().rev() {
+                    write!(f, "{}: ", message)?;
+                }
+                write!(f, "{}", source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PoseidonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PoseidonError::IoErr(error) => Some(error),
+            PoseidonError::Rustls(error) => Some(error),
+            PoseidonError::Tx(error) => Some(error),
+            PoseidonError::Http(error) => Some(error),
+            PoseidonError::Json(error) => Some(error),
+            PoseidonError::Store(error) => Some(error),
+            PoseidonError::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
 }
 
 impl From<hex::FromHexError> for PoseidonError {
@@ -86,6 +379,62 @@ impl From<std::io::Error> for PoseidonError {
     }
 }
 
+impl From<PoseidonIoError> for PoseidonError {
+    fn from(error: PoseidonIoError) -> Self {
+        PoseidonError::IoErr(error)
+    }
+}
+
+impl From<std::str::Utf8Error> for PoseidonError {
+    fn from(error: std::str::Utf8Error) -> Self {
+        PoseidonError::InvalidUtf8Sequence {
+            valid_up_to: error.valid_up_to(),
+            // A UTF-8 error spans at most 3 bytes
+            error_len: error.error_len().map(|error_len| error_len as u8),
+        }
+    }
+}
+
+impl From<std::string::FromUtf8Error> for PoseidonError {
+    fn from(error: std::string::FromUtf8Error) -> Self {
+        error.utf8_error().into()
+    }
+}
+
+impl From<bs58::decode::Error> for PoseidonError {
+    fn from(error: bs58::decode::Error) -> Self {
+        match error {
+            bs58::decode::Error::InvalidCharacter { character, index } => {
+                PoseidonError::InvalidBase58Character {
+                    c: character.to_string(),
+                    index,
+                }
+            }
+            bs58::decode::Error::NonAsciiCharacter { index } => {
+                PoseidonError::NonAsciiBase58Character { index }
+            }
+            bs58::decode::Error::BufferTooSmall => PoseidonError::InvalidBase58Length,
+            // Checksum errors only exist when another crate enables the
+            // `check` feature of `bs58`
+            error => PoseidonError::Unspecified(format!("base58 decoding failed - `{}`", error)),
+        }
+    }
+}
+
+impl From<core::array::TryFromSliceError> for PoseidonError {
+    fn from(_: core::array::TryFromSliceError) -> Self {
+        PoseidonError::InvalidSliceLength
+    }
+}
+
+/// Borsh deserializes `T` from exactly `bytes`. Borsh reports failures as
+/// [std::io::Error], which `?` would turn into [PoseidonError::IoErr], so
+/// this maps them to [PoseidonError::BorshDeserializationFailed] instead.
+pub fn borsh_from_slice<T: BorshDeserialize>(bytes: &[u8]) -> PoseidonResult<T> {
+    T::try_from_slice(bytes)
+        .map_err(|error| PoseidonError::BorshDeserializationFailed(error.to_string()))
+}
+
 #[cfg(feature = "serde_json")]
 impl From<serde_json::Error> for PoseidonError {
     fn from(error: serde_json::Error) -> Self {
@@ -0,0 +1,60 @@
+use crate::{PoseidonError, PoseidonResult};
+
+impl PoseidonError {
+    /// Wraps the error with a message describing the operation that failed.
+    /// Context added to an error that already has some is appended to it.
+    pub fn context<C: Into<String>>(self, message: C) -> PoseidonError {
+        match self {
+            PoseidonError::Context {
+                mut context,
+                source,
+            } => {
+                context.push(message.into());
+                PoseidonError::Context { context, source }
+            }
+            error => PoseidonError::Context {
+                context: vec![message.into()],
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// The context messages from the innermost to the outermost operation
+    pub fn context_chain(&self) -> &[String] {
+        match self {
+            PoseidonError::Context { context, .. } => context,
+            _ => &[],
+        }
+    }
+
+    /// The error without any context
+    pub fn root_cause(&self) -> &PoseidonError {
+        match self {
+            PoseidonError::Context { source, .. } => source.root_cause(),
+            error => error,
+        }
+    }
+}
+
+/// Adds context to the error of any result whose error converts into a
+/// [PoseidonError]
+///
+/// ```ignore
+/// let config = std::fs::read("repo.toml").context("loading repo config")?;
+/// ```
+pub trait ResultExt<T> {
+    fn context<C: Into<String>>(self, message: C) -> PoseidonResult<T>;
+
+    /// Like [ResultExt::context] but only builds the message on error
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, message: F) -> PoseidonResult<T>;
+}
+
+impl<T, E: Into<PoseidonError>> ResultExt<T> for Result<T, E> {
+    fn context<C: Into<String>>(self, message: C) -> PoseidonResult<T> {
+        self.map_err(|error| error.into().context(message))
+    }
+
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, message: F) -> PoseidonResult<T> {
+        self.map_err(|error| error.into().context(message()))
+    }
+}
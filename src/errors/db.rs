@@ -1,9 +1,7 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use core::fmt;
 use serde::{Deserialize, Serialize};
 
-#[derive(
-    Debug, PartialEq, PartialOrd, Eq, Ord, Deserialize, Serialize, BorshDeserialize, BorshSerialize,
-)]
+#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Deserialize, Serialize)]
 pub enum StoreErr {
     PermissionDenied,
     StoreNotFound(String),
@@ -15,3 +13,37 @@ pub enum StoreErr {
     RepoNotFound,
     SubscribeError,
 }
+
+impl_stable_codes! {
+    StoreErr {
+        PermissionDenied = 0,
+        StoreNotFound(details: String) = 1,
+        EntryExists = 2,
+        DeletionErr(details: String) = 3,
+        UpdateError(details: String) = 4,
+        UpsertError(details: String) = 5,
+        RepoPermissionDenied = 6,
+        RepoNotFound = 7,
+        SubscribeError = 8,
+    }
+}
+
+impl fmt::Display for StoreErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreErr::PermissionDenied => f.write_str("permission denied"),
+            StoreErr::StoreNotFound(name) => write!(f, "the store `{}` was not found", name),
+            StoreErr::EntryExists => f.write_str("the entry already exists"),
+            StoreErr::DeletionErr(details) => write!(f, "unable to delete the entry: {}", details),
+            StoreErr::UpdateError(details) => write!(f, "unable to update the entry: {}", details),
+            StoreErr::UpsertError(details) => write!(f, "unable to upsert the entry: {}", details),
+            StoreErr::RepoPermissionDenied => {
+                f.write_str("permission denied while accessing the repository")
+            }
+            StoreErr::RepoNotFound => f.write_str("the repository was not found"),
+            StoreErr::SubscribeError => f.write_str("unable to subscribe to the store"),
+        }
+    }
+}
+
+impl std::error::Error for StoreErr {}
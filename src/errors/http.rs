@@ -1,7 +1,7 @@
-use crate::PoseidonError;
+use crate::{PoseidonError, TransactionError};
 use borsh::{BorshDeserialize, BorshSerialize};
-use serde::{Deserialize, Serialize};
-
+use core::fmt;
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize};
 
 #[derive(
     Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize, BorshSerialize, BorshDeserialize,
@@ -10,12 +10,58 @@ use serde::{Deserialize, Serialize};
 pub struct JsonError {
     pub code: i16,
     pub message: String,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub data: Option<JsonErrorData>,
 }
 
-
+/// The `data` field Solana nodes attach to JSON-RPC errors, e.g. when
+/// `sendTransaction` fails preflight simulation
 #[derive(
     Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize, BorshSerialize, BorshDeserialize,
 )]
+#[serde(rename_all = "camelCase")]
+pub struct JsonErrorData {
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub err: Option<TransactionError>,
+    #[serde(default)]
+    pub logs: Option<Vec<String>>,
+    #[serde(default)]
+    pub units_consumed: Option<u64>,
+}
+
+impl JsonError {
+    /// The transaction error reported in `data.err`, if any
+    pub fn transaction_error(&self) -> Option<&TransactionError> {
+        self.data.as_ref().and_then(|data| data.err.as_ref())
+    }
+}
+
+/// Other methods put unrelated values in `data`, and newer nodes may report
+/// transaction errors this crate does not know yet, neither of which should
+/// make the whole error unreadable
+fn deserialize_lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lenient<T> {
+        Value(T),
+        Other(IgnoredAny),
+    }
+
+    Ok(match Option::<Lenient<T>>::deserialize(deserializer)? {
+        Some(Lenient::Value(value)) => Some(value),
+        _ => None,
+    })
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum HttpError {
     MalformedChunkLength,
     MalformedChunkEnd,
@@ -37,6 +83,83 @@ pub enum HttpError {
     Other(String),
 }
 
+impl_stable_codes! {
+    HttpError {
+        MalformedChunkLength = 0,
+        MalformedChunkEnd = 1,
+        MalformedContentLength = 2,
+        HeadersOverflow = 3,
+        StatusLineOverflow = 4,
+        AddressNotFound = 5,
+        RedirectLocationMissing = 6,
+        InfiniteRedirectionLoop = 7,
+        TooManyRedirections = 8,
+        InvalidUtf8InResponse = 9,
+        PunycodeConversionFailed = 10,
+        HttpsFeatureNotEnabled = 11,
+        PunycodeFeatureNotEnabled = 12,
+        BadProxy = 13,
+        BadProxyCreds = 14,
+        ProxyConnect = 15,
+        InvalidProxyCreds = 16,
+        Other(details: String) = 17,
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::MalformedChunkLength => f.write_str("malformed chunk length"),
+            HttpError::MalformedChunkEnd => f.write_str("malformed chunk end"),
+            HttpError::MalformedContentLength => f.write_str("malformed Content-Length header"),
+            HttpError::HeadersOverflow => f.write_str("the response headers are too large"),
+            HttpError::StatusLineOverflow => f.write_str("the response status line is too long"),
+            HttpError::AddressNotFound => f.write_str("the address could not be resolved"),
+            HttpError::RedirectLocationMissing => {
+                f.write_str("redirect response without a Location header")
+            }
+            HttpError::InfiniteRedirectionLoop => f.write_str("infinite redirection loop"),
+            HttpError::TooManyRedirections => f.write_str("too many redirections"),
+            HttpError::InvalidUtf8InResponse => f.write_str("the response is not valid UTF-8"),
+            HttpError::PunycodeConversionFailed => f.write_str("punycode conversion failed"),
+            HttpError::HttpsFeatureNotEnabled => f.write_str("HTTPS support is not enabled"),
+            HttpError::PunycodeFeatureNotEnabled => f.write_str("punycode support is not enabled"),
+            HttpError::BadProxy => f.write_str("invalid proxy"),
+            HttpError::BadProxyCreds => f.write_str("invalid proxy credentials"),
+            HttpError::ProxyConnect => f.write_str("unable to connect to the proxy"),
+            HttpError::InvalidProxyCreds => f.write_str("the proxy rejected the credentials"),
+            HttpError::Other(details) => f.write_str(details),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Errors that carry a transaction error become [PoseidonError::Tx], errors
+/// in the Poseidon range are rebuilt from their code, other errors are kept as
+/// [PoseidonError::Json]
+impl From<JsonError> for PoseidonError {
+    fn from(error: JsonError) -> Self {
+        if let Some(transaction_error) = error.transaction_error().cloned() {
+            return PoseidonError::Tx(transaction_error);
+        }
+
+        match error.poseidon_code() {
+            Some(code) => PoseidonError::from_code(code, error.message),
+            None => PoseidonError::Json(error),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
 impl From<minreq::Error> for PoseidonError {
     fn from(error: minreq::Error) -> Self {
         use minreq::Error as MinreqError;
@@ -1,9 +1,9 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use core::fmt;
 use serde::{Deserialize, Serialize};
+use std::{io::ErrorKind, path::Path};
 
-#[derive(
-    Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize, BorshSerialize, BorshDeserialize,
-)]
+#[derive(Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum PoseidonErrorKind {
     NotFound,
     PermissionDenied,
@@ -26,34 +26,304 @@ pub enum PoseidonErrorKind {
     OutOfMemory,
     Other,
     Unspecified(String),
+    HostUnreachable,
+    NetworkUnreachable,
+    NetworkDown,
+    NotADirectory,
+    IsADirectory,
+    DirectoryNotEmpty,
+    ReadOnlyFilesystem,
+    StaleNetworkFileHandle,
+    StorageFull,
+    NotSeekable,
+    QuotaExceeded,
+    FileTooLarge,
+    ResourceBusy,
+    ExecutableFileBusy,
+    Deadlock,
+    CrossesDevices,
+    TooManyLinks,
+    InvalidFilename,
+    ArgumentListTooLong,
+}
+
+impl_stable_codes! {
+    PoseidonErrorKind {
+        NotFound = 0,
+        PermissionDenied = 1,
+        ConnectionRefused = 2,
+        ConnectionReset = 3,
+        NotConnected = 4,
+        ConnectionAborted = 5,
+        AddrInUse = 6,
+        AddrNotAvailable = 7,
+        AlreadyExists = 8,
+        WouldBlock = 9,
+        InvalidInput = 10,
+        InvalidData = 11,
+        BrokenPipe = 12,
+        TimedOut = 13,
+        WriteZero = 14,
+        Interrupted = 15,
+        Unsupported = 16,
+        UnexpectedEof = 17,
+        OutOfMemory = 18,
+        Other = 19,
+        Unspecified(details: String) = 20,
+        HostUnreachable = 21,
+        NetworkUnreachable = 22,
+        NetworkDown = 23,
+        NotADirectory = 24,
+        IsADirectory = 25,
+        DirectoryNotEmpty = 26,
+        ReadOnlyFilesystem = 27,
+        StaleNetworkFileHandle = 28,
+        StorageFull = 29,
+        NotSeekable = 30,
+        QuotaExceeded = 31,
+        FileTooLarge = 32,
+        ResourceBusy = 33,
+        ExecutableFileBusy = 34,
+        Deadlock = 35,
+        CrossesDevices = 36,
+        TooManyLinks = 37,
+        InvalidFilename = 38,
+        ArgumentListTooLong = 39,
+    }
 }
 
 impl From<std::io::Error> for PoseidonErrorKind {
     fn from(error: std::io::Error) -> Self {
         match error.kind() {
-            std::io::ErrorKind::NotFound => PoseidonErrorKind::NotFound,
-            std::io::ErrorKind::PermissionDenied => PoseidonErrorKind::PermissionDenied,
-            std::io::ErrorKind::ConnectionRefused => PoseidonErrorKind::ConnectionRefused,
-            std::io::ErrorKind::ConnectionReset => PoseidonErrorKind::ConnectionReset,
-            std::io::ErrorKind::ConnectionAborted => PoseidonErrorKind::ConnectionAborted,
-            std::io::ErrorKind::NotConnected => PoseidonErrorKind::NotConnected,
-            std::io::ErrorKind::AddrInUse => PoseidonErrorKind::AddrInUse,
-            std::io::ErrorKind::AddrNotAvailable => PoseidonErrorKind::AddrNotAvailable,
-            std::io::ErrorKind::BrokenPipe => PoseidonErrorKind::BrokenPipe,
-            std::io::ErrorKind::AlreadyExists => PoseidonErrorKind::AlreadyExists,
-            std::io::ErrorKind::WouldBlock => PoseidonErrorKind::WouldBlock,
-            std::io::ErrorKind::InvalidInput => PoseidonErrorKind::InvalidInput,
-            std::io::ErrorKind::InvalidData => PoseidonErrorKind::InvalidData,
-            std::io::ErrorKind::TimedOut => PoseidonErrorKind::TimedOut,
-            std::io::ErrorKind::WriteZero => PoseidonErrorKind::WriteZero,
-            std::io::ErrorKind::Interrupted => PoseidonErrorKind::Interrupted,
-            std::io::ErrorKind::Unsupported => PoseidonErrorKind::Unsupported,
-            std::io::ErrorKind::UnexpectedEof => PoseidonErrorKind::UnexpectedEof,
-            std::io::ErrorKind::OutOfMemory => PoseidonErrorKind::OutOfMemory,
-            std::io::ErrorKind::Other => PoseidonErrorKind::Other,
-            _ => {
-                PoseidonErrorKind::Unspecified(format!("std::io::Error - `{}`", error.to_string()))
+            ErrorKind::NotFound => PoseidonErrorKind::NotFound,
+            ErrorKind::PermissionDenied => PoseidonErrorKind::PermissionDenied,
+            ErrorKind::ConnectionRefused => PoseidonErrorKind::ConnectionRefused,
+            ErrorKind::ConnectionReset => PoseidonErrorKind::ConnectionReset,
+            ErrorKind::ConnectionAborted => PoseidonErrorKind::ConnectionAborted,
+            ErrorKind::NotConnected => PoseidonErrorKind::NotConnected,
+            ErrorKind::AddrInUse => PoseidonErrorKind::AddrInUse,
+            ErrorKind::AddrNotAvailable => PoseidonErrorKind::AddrNotAvailable,
+            ErrorKind::BrokenPipe => PoseidonErrorKind::BrokenPipe,
+            ErrorKind::AlreadyExists => PoseidonErrorKind::AlreadyExists,
+            ErrorKind::WouldBlock => PoseidonErrorKind::WouldBlock,
+            ErrorKind::InvalidInput => PoseidonErrorKind::InvalidInput,
+            ErrorKind::InvalidData => PoseidonErrorKind::InvalidData,
+            ErrorKind::TimedOut => PoseidonErrorKind::TimedOut,
+            ErrorKind::WriteZero => PoseidonErrorKind::WriteZero,
+            ErrorKind::Interrupted => PoseidonErrorKind::Interrupted,
+            ErrorKind::Unsupported => PoseidonErrorKind::Unsupported,
+            ErrorKind::UnexpectedEof => PoseidonErrorKind::UnexpectedEof,
+            ErrorKind::OutOfMemory => PoseidonErrorKind::OutOfMemory,
+            ErrorKind::Other => PoseidonErrorKind::Other,
+            ErrorKind::HostUnreachable => PoseidonErrorKind::HostUnreachable,
+            ErrorKind::NetworkUnreachable => PoseidonErrorKind::NetworkUnreachable,
+            ErrorKind::NetworkDown => PoseidonErrorKind::NetworkDown,
+            ErrorKind::NotADirectory => PoseidonErrorKind::NotADirectory,
+            ErrorKind::IsADirectory => PoseidonErrorKind::IsADirectory,
+            ErrorKind::DirectoryNotEmpty => PoseidonErrorKind::DirectoryNotEmpty,
+            ErrorKind::ReadOnlyFilesystem => PoseidonErrorKind::ReadOnlyFilesystem,
+            ErrorKind::StaleNetworkFileHandle => PoseidonErrorKind::StaleNetworkFileHandle,
+            ErrorKind::StorageFull => PoseidonErrorKind::StorageFull,
+            ErrorKind::NotSeekable => PoseidonErrorKind::NotSeekable,
+            ErrorKind::QuotaExceeded => PoseidonErrorKind::QuotaExceeded,
+            ErrorKind::FileTooLarge => PoseidonErrorKind::FileTooLarge,
+            ErrorKind::ResourceBusy => PoseidonErrorKind::ResourceBusy,
+            ErrorKind::ExecutableFileBusy => PoseidonErrorKind::ExecutableFileBusy,
+            ErrorKind::Deadlock => PoseidonErrorKind::Deadlock,
+            ErrorKind::CrossesDevices => PoseidonErrorKind::CrossesDevices,
+            ErrorKind::TooManyLinks => PoseidonErrorKind::TooManyLinks,
+            ErrorKind::InvalidFilename => PoseidonErrorKind::InvalidFilename,
+            ErrorKind::ArgumentListTooLong => PoseidonErrorKind::ArgumentListTooLong,
+            _ => PoseidonErrorKind::Unspecified(format!("std::io::Error - `{}`", error)),
+        }
+    }
+}
+
+impl From<&PoseidonErrorKind> for ErrorKind {
+    fn from(kind: &PoseidonErrorKind) -> Self {
+        match kind {
+            PoseidonErrorKind::NotFound => ErrorKind::NotFound,
+            PoseidonErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+            PoseidonErrorKind::ConnectionRefused => ErrorKind::ConnectionRefused,
+            PoseidonErrorKind::ConnectionReset => ErrorKind::ConnectionReset,
+            PoseidonErrorKind::ConnectionAborted => ErrorKind::ConnectionAborted,
+            PoseidonErrorKind::NotConnected => ErrorKind::NotConnected,
+            PoseidonErrorKind::AddrInUse => ErrorKind::AddrInUse,
+            PoseidonErrorKind::AddrNotAvailable => ErrorKind::AddrNotAvailable,
+            PoseidonErrorKind::BrokenPipe => ErrorKind::BrokenPipe,
+            PoseidonErrorKind::AlreadyExists => ErrorKind::AlreadyExists,
+            PoseidonErrorKind::WouldBlock => ErrorKind::WouldBlock,
+            PoseidonErrorKind::InvalidInput => ErrorKind::InvalidInput,
+            PoseidonErrorKind::InvalidData => ErrorKind::InvalidData,
+            PoseidonErrorKind::TimedOut => ErrorKind::TimedOut,
+            PoseidonErrorKind::WriteZero => ErrorKind::WriteZero,
+            PoseidonErrorKind::Interrupted => ErrorKind::Interrupted,
+            PoseidonErrorKind::Unsupported => ErrorKind::Unsupported,
+            PoseidonErrorKind::UnexpectedEof => ErrorKind::UnexpectedEof,
+            PoseidonErrorKind::OutOfMemory => ErrorKind::OutOfMemory,
+            PoseidonErrorKind::Other => ErrorKind::Other,
+            PoseidonErrorKind::HostUnreachable => ErrorKind::HostUnreachable,
+            PoseidonErrorKind::NetworkUnreachable => ErrorKind::NetworkUnreachable,
+            PoseidonErrorKind::NetworkDown => ErrorKind::NetworkDown,
+            PoseidonErrorKind::NotADirectory => ErrorKind::NotADirectory,
+            PoseidonErrorKind::IsADirectory => ErrorKind::IsADirectory,
+            PoseidonErrorKind::DirectoryNotEmpty => ErrorKind::DirectoryNotEmpty,
+            PoseidonErrorKind::ReadOnlyFilesystem => ErrorKind::ReadOnlyFilesystem,
+            PoseidonErrorKind::StaleNetworkFileHandle => ErrorKind::StaleNetworkFileHandle,
+            PoseidonErrorKind::StorageFull => ErrorKind::StorageFull,
+            PoseidonErrorKind::NotSeekable => ErrorKind::NotSeekable,
+            PoseidonErrorKind::QuotaExceeded => ErrorKind::QuotaExceeded,
+            PoseidonErrorKind::FileTooLarge => ErrorKind::FileTooLarge,
+            PoseidonErrorKind::ResourceBusy => ErrorKind::ResourceBusy,
+            PoseidonErrorKind::ExecutableFileBusy => ErrorKind::ExecutableFileBusy,
+            PoseidonErrorKind::Deadlock => ErrorKind::Deadlock,
+            PoseidonErrorKind::CrossesDevices => ErrorKind::CrossesDevices,
+            PoseidonErrorKind::TooManyLinks => ErrorKind::TooManyLinks,
+            PoseidonErrorKind::InvalidFilename => ErrorKind::InvalidFilename,
+            PoseidonErrorKind::ArgumentListTooLong => ErrorKind::ArgumentListTooLong,
+            PoseidonErrorKind::Unspecified(..) => ErrorKind::Other,
+        }
+    }
+}
+
+/// An I/O error that keeps what [std::io::Error] knows about the failure,
+/// plus the path it happened on when the caller provides it
+#[derive(
+    Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct PoseidonIoError {
+    pub kind: PoseidonErrorKind,
+    pub raw_os_error: Option<RawOsError>,
+    pub message: String,
+    pub path: Option<String>,
+}
+
+/// The `errno` on unix or the `GetLastError` code on windows. The same code
+/// means different errors on different systems, so it is only meaningful on
+/// the system it was recorded on.
+#[derive(
+    Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct RawOsError {
+    pub code: i32,
+    /// The [std::env::consts::OS] of the system that recorded the code
+    pub os: String,
+}
+
+impl RawOsError {
+    /// A code recorded on the current system
+    pub fn new(code: i32) -> Self {
+        RawOsError {
+            code,
+            os: std::env::consts::OS.to_owned(),
+        }
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.os == std::env::consts::OS
+    }
+}
+
+impl PoseidonIoError {
+    pub fn with_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.path = Some(path.as_ref().to_string_lossy().into_owned());
+        self
+    }
+}
+
+impl From<std::io::Error> for PoseidonIoError {
+    fn from(error: std::io::Error) -> Self {
+        PoseidonIoError {
+            raw_os_error: error.raw_os_error().map(RawOsError::new),
+            message: error.to_string(),
+            path: None,
+            kind: error.into(),
+        }
+    }
+}
+
+impl From<PoseidonErrorKind> for PoseidonIoError {
+    fn from(kind: PoseidonErrorKind) -> Self {
+        PoseidonIoError {
+            message: kind.to_string(),
+            raw_os_error: None,
+            path: None,
+            kind,
+        }
+    }
+}
+
+/// OS errors recorded on this system are rebuilt from their raw code so they
+/// compare and display like the original, and drop the path, which is not
+/// part of a [std::io::Error]. Other errors keep their kind, and their
+/// message includes the path.
+impl From<PoseidonIoError> for std::io::Error {
+    fn from(error: PoseidonIoError) -> Self {
+        match &error.raw_os_error {
+            Some(raw) if raw.is_native() => std::io::Error::from_raw_os_error(raw.code),
+            _ => std::io::Error::new(ErrorKind::from(&error.kind), error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for PoseidonIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "`{}`: {}", path, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for PoseidonIoError {}
+
+impl fmt::Display for PoseidonErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoseidonErrorKind::NotFound => f.write_str("entity not found"),
+            PoseidonErrorKind::PermissionDenied => f.write_str("permission denied"),
+            PoseidonErrorKind::ConnectionRefused => f.write_str("connection refused"),
+            PoseidonErrorKind::ConnectionReset => f.write_str("connection reset"),
+            PoseidonErrorKind::NotConnected => f.write_str("not connected"),
+            PoseidonErrorKind::ConnectionAborted => f.write_str("connection aborted"),
+            PoseidonErrorKind::AddrInUse => f.write_str("address in use"),
+            PoseidonErrorKind::AddrNotAvailable => f.write_str("address not available"),
+            PoseidonErrorKind::AlreadyExists => f.write_str("entity already exists"),
+            PoseidonErrorKind::WouldBlock => f.write_str("operation would block"),
+            PoseidonErrorKind::InvalidInput => f.write_str("invalid input parameter"),
+            PoseidonErrorKind::InvalidData => f.write_str("invalid data"),
+            PoseidonErrorKind::BrokenPipe => f.write_str("broken pipe"),
+            PoseidonErrorKind::TimedOut => f.write_str("timed out"),
+            PoseidonErrorKind::WriteZero => f.write_str("write zero"),
+            PoseidonErrorKind::Interrupted => f.write_str("operation interrupted"),
+            PoseidonErrorKind::Unsupported => f.write_str("unsupported"),
+            PoseidonErrorKind::UnexpectedEof => f.write_str("unexpected end of file"),
+            PoseidonErrorKind::OutOfMemory => f.write_str("out of memory"),
+            PoseidonErrorKind::Other => f.write_str("other error"),
+            PoseidonErrorKind::Unspecified(details) => f.write_str(details),
+            PoseidonErrorKind::HostUnreachable => f.write_str("host unreachable"),
+            PoseidonErrorKind::NetworkUnreachable => f.write_str("network unreachable"),
+            PoseidonErrorKind::NetworkDown => f.write_str("network down"),
+            PoseidonErrorKind::NotADirectory => f.write_str("not a directory"),
+            PoseidonErrorKind::IsADirectory => f.write_str("is a directory"),
+            PoseidonErrorKind::DirectoryNotEmpty => f.write_str("directory not empty"),
+            PoseidonErrorKind::ReadOnlyFilesystem => {
+                f.write_str("read-only filesystem or storage medium")
             }
+            PoseidonErrorKind::StaleNetworkFileHandle => f.write_str("stale network file handle"),
+            PoseidonErrorKind::StorageFull => f.write_str("no storage space"),
+            PoseidonErrorKind::NotSeekable => f.write_str("seek on unseekable file"),
+            PoseidonErrorKind::QuotaExceeded => f.write_str("quota exceeded"),
+            PoseidonErrorKind::FileTooLarge => f.write_str("file too large"),
+            PoseidonErrorKind::ResourceBusy => f.write_str("resource busy"),
+            PoseidonErrorKind::ExecutableFileBusy => f.write_str("executable file busy"),
+            PoseidonErrorKind::Deadlock => f.write_str("deadlock"),
+            PoseidonErrorKind::CrossesDevices => f.write_str("cross-device link or rename"),
+            PoseidonErrorKind::TooManyLinks => f.write_str("too many links"),
+            PoseidonErrorKind::InvalidFilename => f.write_str("invalid filename"),
+            PoseidonErrorKind::ArgumentListTooLong => f.write_str("argument list too long"),
         }
     }
 }
+
+impl std::error::Error for PoseidonErrorKind {}
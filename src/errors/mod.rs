@@ -1,3 +1,60 @@
+// The error enums nested in `PoseidonError` are encoded as the code of the
+// variant, a `u8`, followed by its fields. Codes are never changed or reused
+// and new variants take the next free code, so the encoding does not depend
+// on the order of the variants. The codes are the variant indices the derived
+// borsh encoding used before, which keeps that encoding readable.
+macro_rules! impl_stable_codes {
+    ($name:ident {
+        $($variant:ident
+            $(($($field:ident: $field_type:ty),*))?
+            $({$($named:ident: $named_type:ty),*})?
+            = $code:literal,)*
+    }) => {
+        impl $name {
+            /// The stable code of the variant
+            pub fn code(&self) -> u32 {
+                match self {
+                    $($name::$variant { .. } => $code,)*
+                }
+            }
+        }
+
+        impl borsh::BorshSerialize for $name {
+            fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+                match self {
+                    $($name::$variant $(($($field),*))? $({$($named),*})? => {
+                        writer.write_all(&[$code])?;
+                        $($(borsh::BorshSerialize::serialize($field, writer)?;)*)?
+                        $($(borsh::BorshSerialize::serialize($named, writer)?;)*)?
+                    })*
+                }
+
+                Ok(())
+            }
+        }
+
+        impl borsh::BorshDeserialize for $name {
+            fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+                let code = <u8 as borsh::BorshDeserialize>::deserialize(buf)?;
+
+                Ok(match code {
+                    $($code => $name::$variant
+                        $(($(<$field_type as borsh::BorshDeserialize>::deserialize(buf)?),*))?
+                        $({$(
+                            $named: <$named_type as borsh::BorshDeserialize>::deserialize(buf)?
+                        ),*})?,)*
+                    _ => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("unknown {} code {}", stringify!($name), code),
+                        ))
+                    }
+                })
+            }
+        }
+    };
+}
+
 mod common;
 pub use common::*;
 
@@ -7,23 +64,29 @@ pub use io::*;
 mod db;
 pub use db::*;
 
+mod wire;
+pub use wire::*;
 
-#[cfg(feature = "http")]
+mod class;
+pub use class::*;
+
+mod context;
+pub use context::*;
+
+mod rpc;
+pub use rpc::*;
+
+// The error types below are always compiled so that `PoseidonError` has the
+// same layout whatever features are enabled, the features only gate the
+// conversions from the underlying crates
 mod http;
-#[cfg(feature = "http")]
 pub use http::*;
 
-#[cfg(feature = "rustls")]
 mod ssl;
-#[cfg(feature = "rustls")]
 pub use ssl::*;
 
 #[cfg(feature = "sled_kv")]
 mod sled_errors;
-#[cfg(feature = "sled_kv")]
-pub use sled_errors::*;
 
-#[cfg(feature = "solana_client")]
 mod transaction_error;
-#[cfg(feature = "solana_client")]
 pub use transaction_error::*;
@@ -0,0 +1,163 @@
+//! Mapping of [PoseidonError] to the responses of our gateways, a JSON-RPC
+//! error object and an HTTP status code, and back to errors for clients.
+//!
+//! JSON-RPC codes follow the ranges of JSON-RPC 2.0 and of Solana nodes:
+//!
+//! | Codes           | Errors                                                 |
+//! |-----------------|--------------------------------------------------------|
+//! | -32768..=-32000 | Reserved by JSON-RPC 2.0, [PoseidonError::Json] as is  |
+//! | -32002          | [PoseidonError::Tx], the error is sent in `data.err`   |
+//! | -32603          | [PoseidonError::Unspecified], internal error           |
+//! | -29999..=-20000 | Other errors, `-20000 - code` of [PoseidonError::code] |
+
+use crate::{
+    ErrorClass, JsonError, JsonErrorData, PoseidonError, PoseidonErrorKind, PoseidonIoError,
+    StoreErr, TransactionError,
+};
+use core::ops::RangeInclusive;
+
+/// JSON-RPC 2.0 code of an internal error
+pub const JSON_RPC_INTERNAL_ERROR: i16 = -32603;
+
+/// Solana node code of a transaction that failed preflight simulation
+pub const JSON_RPC_TRANSACTION_FAILED: i16 = -32002;
+
+/// JSON-RPC codes of Poseidon errors, see [poseidon_json_rpc_code]
+pub const POSEIDON_JSON_RPC_CODES: RangeInclusive<i16> = -29_999..=-20_000;
+
+/// The JSON-RPC code of a [PoseidonError::code], `None` for codes that do not
+/// fit in the Poseidon range
+pub fn poseidon_json_rpc_code(code: u32) -> Option<i16> {
+    i16::try_from(code)
+        .ok()
+        .and_then(|code| POSEIDON_JSON_RPC_CODES.end().checked_sub(code))
+        .filter(|code| POSEIDON_JSON_RPC_CODES.contains(code))
+}
+
+impl From<PoseidonError> for JsonError {
+    fn from(error: PoseidonError) -> Self {
+        let message = match &error {
+            PoseidonError::Unknown { message, .. } => message.clone(),
+            error => error.to_string(),
+        };
+
+        match error {
+            PoseidonError::Context { source, .. } => JsonError {
+                message,
+                ..JsonError::from(*source)
+            },
+            PoseidonError::Json(error) => error,
+            PoseidonError::Tx(error) => JsonError {
+                code: JSON_RPC_TRANSACTION_FAILED,
+                message,
+                data: Some(JsonErrorData {
+                    err: Some(error),
+                    logs: None,
+                    units_consumed: None,
+                }),
+            },
+            error => JsonError {
+                code: match error {
+                    PoseidonError::Unspecified(..) => None,
+                    error => poseidon_json_rpc_code(error.code()),
+                }
+                .unwrap_or(JSON_RPC_INTERNAL_ERROR),
+                message,
+                data: None,
+            },
+        }
+    }
+}
+
+impl JsonError {
+    /// The [PoseidonError::code] of errors in the Poseidon range
+    pub fn poseidon_code(&self) -> Option<u32> {
+        if POSEIDON_JSON_RPC_CODES.contains(&self.code) {
+            Some((POSEIDON_JSON_RPC_CODES.end() - self.code) as u32)
+        } else {
+            None
+        }
+    }
+}
+
+impl PoseidonError {
+    /// The HTTP status a gateway responds with for the error
+    pub fn http_status(&self) -> u16 {
+        match self.root_cause() {
+            PoseidonError::AccountNotFound
+            | PoseidonError::SledCollectionNotFound(..)
+            | PoseidonError::Store(StoreErr::StoreNotFound(..) | StoreErr::RepoNotFound) => 404,
+            PoseidonError::RepoCreatePermissionDenied
+            | PoseidonError::KeystoreWrongPassword
+            | PoseidonError::Store(StoreErr::PermissionDenied | StoreErr::RepoPermissionDenied) => {
+                403
+            }
+            PoseidonError::RepoAlreadyExists | PoseidonError::Store(StoreErr::EntryExists) => 409,
+            PoseidonError::DecodedSizeLimitExceeded { .. } => 413,
+            PoseidonError::InvalidMimeType(..)
+            | PoseidonError::MimeTypeMismatch { .. }
+            | PoseidonError::UnsupportedEncoding(..) => 415,
+            PoseidonError::IoErr(error) => error.kind.http_status(),
+            PoseidonError::Tx(TransactionError::ClusterMaintenance) => 503,
+            // Method not found
+            PoseidonError::Json(JsonError { code: -32601, .. }) => 404,
+            // The upstream node could not be reached
+            PoseidonError::Http(..) | PoseidonError::Rustls(..) => 502,
+            error => error.class().http_status(),
+        }
+    }
+
+    /// The error for an HTTP response that carries no JSON-RPC error, `None`
+    /// for statuses that are not errors. The status becomes the closest
+    /// [PoseidonErrorKind] so that the error is classified like an I/O error.
+    pub fn from_http_status<M: Into<String>>(status: u16, message: M) -> Option<PoseidonError> {
+        let kind = match status {
+            0..=399 => return None,
+            400 | 422 => PoseidonErrorKind::InvalidInput,
+            401 | 403 => PoseidonErrorKind::PermissionDenied,
+            404 | 410 => PoseidonErrorKind::NotFound,
+            408 | 504 => PoseidonErrorKind::TimedOut,
+            409 => PoseidonErrorKind::AlreadyExists,
+            413 => PoseidonErrorKind::FileTooLarge,
+            415 => PoseidonErrorKind::Unsupported,
+            429 | 503 => PoseidonErrorKind::ResourceBusy,
+            502 => PoseidonErrorKind::ConnectionAborted,
+            507 => PoseidonErrorKind::StorageFull,
+            _ => PoseidonErrorKind::Other,
+        };
+
+        let mut error = PoseidonIoError::from(kind);
+        let message = message.into();
+        if !message.is_empty() {
+            error.message = message;
+        }
+
+        Some(PoseidonError::IoErr(error))
+    }
+}
+
+impl PoseidonErrorKind {
+    pub fn http_status(&self) -> u16 {
+        match self {
+            PoseidonErrorKind::NotFound => 404,
+            PoseidonErrorKind::PermissionDenied => 403,
+            PoseidonErrorKind::AlreadyExists => 409,
+            PoseidonErrorKind::TimedOut => 504,
+            PoseidonErrorKind::FileTooLarge => 413,
+            PoseidonErrorKind::StorageFull | PoseidonErrorKind::QuotaExceeded => 507,
+            kind => kind.class().http_status(),
+        }
+    }
+}
+
+impl ErrorClass {
+    /// The generic status of the class, `503` for transient errors, `400`
+    /// for user errors and `500` otherwise
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ErrorClass::Transient => 503,
+            ErrorClass::User => 400,
+            ErrorClass::Permanent => 500,
+        }
+    }
+}
@@ -1,13 +1,13 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use core::fmt;
+#[cfg(feature = "rustls")]
 use rustls::internal::msgs::enums::{
     AlertDescription as RustlsAlertDescription, ContentType as RustlsContentType,
     HandshakeType as RustlsHandshakeType,
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(
-    Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize, BorshSerialize, BorshDeserialize,
-)]
+#[derive(Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum RustlsError {
     InappropriateMessage {
         expect_types: Vec<ContentType>,
@@ -40,6 +40,41 @@ pub enum RustlsError {
     BadMaxFragmentSize,
 }
 
+impl_stable_codes! {
+    RustlsError {
+        InappropriateMessage {
+            expect_types: Vec<ContentType>,
+            got_type: ContentType
+        } = 0,
+        InappropriateHandshakeMessage {
+            expect_types: Vec<HandshakeType>,
+            got_type: HandshakeType
+        } = 1,
+        CorruptMessage = 2,
+        CorruptMessagePayload(content_type: ContentType) = 3,
+        NoCertificatesPresented = 4,
+        UnsupportedNameType = 5,
+        DecryptError = 6,
+        EncryptError = 7,
+        PeerIncompatibleError(details: String) = 8,
+        PeerMisbehavedError(details: String) = 9,
+        AlertReceived(alert: AlertDescription) = 10,
+        InvalidCertificateEncoding = 11,
+        InvalidCertificateSignatureType = 12,
+        InvalidCertificateSignature = 13,
+        InvalidCertificateData(details: String) = 14,
+        InvalidSct(error: SctError) = 15,
+        General(details: String) = 16,
+        FailedToGetCurrentTime = 17,
+        FailedToGetRandomBytes = 18,
+        HandshakeNotComplete = 19,
+        PeerSentOversizedRecord = 20,
+        NoApplicationProtocol = 21,
+        BadMaxFragmentSize = 22,
+    }
+}
+
+#[cfg(feature = "rustls")]
 impl From<rustls::Error> for RustlsError {
     fn from(error: rustls::Error) -> Self {
         match error {
@@ -104,9 +139,80 @@ impl From<rustls::Error> for RustlsError {
     }
 }
 
-#[derive(
-    Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize, BorshSerialize, BorshDeserialize,
-)]
+impl fmt::Display for RustlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RustlsError::InappropriateMessage {
+                expect_types,
+                got_type,
+            } => write!(
+                f,
+                "received unexpected message: got {:?} when expecting {:?}",
+                got_type, expect_types
+            ),
+            RustlsError::InappropriateHandshakeMessage {
+                expect_types,
+                got_type,
+            } => write!(
+                f,
+                "received unexpected handshake message: got {:?} when expecting {:?}",
+                got_type, expect_types
+            ),
+            RustlsError::CorruptMessage => f.write_str("received corrupt message"),
+            RustlsError::CorruptMessagePayload(content_type) => {
+                write!(f, "received corrupt message of type {:?}", content_type)
+            }
+            RustlsError::NoCertificatesPresented => f.write_str("peer sent no certificates"),
+            RustlsError::UnsupportedNameType => {
+                f.write_str("presented server name type wasn't supported")
+            }
+            RustlsError::DecryptError => f.write_str("cannot decrypt peer's message"),
+            RustlsError::EncryptError => f.write_str("cannot encrypt message"),
+            RustlsError::PeerIncompatibleError(details) => {
+                write!(f, "peer is incompatible: {}", details)
+            }
+            RustlsError::PeerMisbehavedError(details) => {
+                write!(f, "peer misbehaved: {}", details)
+            }
+            RustlsError::AlertReceived(alert) => write!(f, "received fatal alert: {:?}", alert),
+            RustlsError::InvalidCertificateEncoding => {
+                f.write_str("invalid peer certificate encoding")
+            }
+            RustlsError::InvalidCertificateSignatureType => {
+                f.write_str("invalid peer certificate signature type")
+            }
+            RustlsError::InvalidCertificateSignature => {
+                f.write_str("invalid peer certificate signature")
+            }
+            RustlsError::InvalidCertificateData(details) => {
+                write!(f, "invalid peer certificate contents: {}", details)
+            }
+            RustlsError::InvalidSct(error) => write!(f, "invalid certificate timestamp: {}", error),
+            RustlsError::General(details) => write!(f, "unexpected error: {}", details),
+            RustlsError::FailedToGetCurrentTime => f.write_str("failed to get current time"),
+            RustlsError::FailedToGetRandomBytes => f.write_str("failed to get random bytes"),
+            RustlsError::HandshakeNotComplete => f.write_str("handshake not complete"),
+            RustlsError::PeerSentOversizedRecord => f.write_str("peer sent excess record size"),
+            RustlsError::NoApplicationProtocol => {
+                f.write_str("peer doesn't support any known protocol")
+            }
+            RustlsError::BadMaxFragmentSize => {
+                f.write_str("the supplied max_fragment_size was too small or large")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RustlsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RustlsError::InvalidSct(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum SctError {
     MalformedSct,
     InvalidSignature,
@@ -115,6 +221,31 @@ pub enum SctError {
     UnknownLog,
 }
 
+impl_stable_codes! {
+    SctError {
+        MalformedSct = 0,
+        InvalidSignature = 1,
+        TimestampInFuture = 2,
+        UnsupportedSctVersion = 3,
+        UnknownLog = 4,
+    }
+}
+
+impl fmt::Display for SctError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SctError::MalformedSct => f.write_str("malformed SCT"),
+            SctError::InvalidSignature => f.write_str("invalid SCT signature"),
+            SctError::TimestampInFuture => f.write_str("SCT timestamp is in the future"),
+            SctError::UnsupportedSctVersion => f.write_str("unsupported SCT version"),
+            SctError::UnknownLog => f.write_str("SCT is from an unknown log"),
+        }
+    }
+}
+
+impl std::error::Error for SctError {}
+
+#[cfg(feature = "rustls")]
 impl From<sct::Error> for SctError {
     fn from(error: sct::Error) -> Self {
         match error {
@@ -168,6 +299,7 @@ pub enum AlertDescription {
     Unknown(u8),
 }
 
+#[cfg(feature = "rustls")]
 impl From<RustlsAlertDescription> for AlertDescription {
     fn from(error: RustlsAlertDescription) -> Self {
         match error {
@@ -234,6 +366,7 @@ pub enum ContentType {
     Unknown(u8),
 }
 
+#[cfg(feature = "rustls")]
 impl From<RustlsContentType> for ContentType {
     fn from(error: RustlsContentType) -> Self {
         match error {
@@ -272,6 +405,7 @@ pub enum HandshakeType {
     Unknown(u8),
 }
 
+#[cfg(feature = "rustls")]
 impl From<RustlsHandshakeType> for HandshakeType {
     fn from(error: RustlsHandshakeType) -> Self {
         match error {
@@ -1,18 +1,10 @@
-use borsh::{BorshDeserialize, BorshSerialize};
-use serde::{Deserialize, Serialize};
-
-#[derive(
-    Debug,
-    Serialize,
-    Deserialize,
-    Clone,
-    PartialEq,
-    PartialOrd,
-    Ord,
-    Eq,
-    BorshSerialize,
-    BorshDeserialize,
-)]
+use core::fmt;
+use serde::{
+    de::{self, EnumAccess, MapAccess, VariantAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd, Ord, Eq)]
 pub enum TransactionError {
     AccountInUse,
     AccountLoadedTwice,
@@ -44,20 +36,70 @@ pub enum TransactionError {
     InvalidRentPayingAccount,
     WouldExceedMaxVoteCostLimit,
     WouldExceedAccountDataTotalLimit,
+    DuplicateInstruction(u8),
+    InsufficientFundsForRent {
+        account_index: u8,
+    },
+    MaxLoadedAccountsDataSizeExceeded,
+    InvalidLoadedAccountsDataSizeLimit,
+    ResanitizationNeeded,
+    ProgramExecutionTemporarilyRestricted {
+        account_index: u8,
+    },
+    UnbalancedTransaction,
+    ProgramCacheHitMaxLimit,
+    CommitCancelled,
+    /// A transaction error this crate does not know yet, as the JSON the
+    /// node returned
+    Unknown(String),
+}
+
+impl_stable_codes! {
+    TransactionError {
+        AccountInUse = 0,
+        AccountLoadedTwice = 1,
+        AccountNotFound = 2,
+        ProgramAccountNotFound = 3,
+        InsufficientFundsForFee = 4,
+        InvalidAccountForFee = 5,
+        AlreadyProcessed = 6,
+        BlockhashNotFound = 7,
+        InstructionError(index: u8, error: InstructionError) = 8,
+        CallChainTooDeep = 9,
+        MissingSignatureForFee = 10,
+        InvalidAccountIndex = 11,
+        SignatureFailure = 12,
+        InvalidProgramForExecution = 13,
+        SanitizeFailure = 14,
+        ClusterMaintenance = 15,
+        AccountBorrowOutstanding = 16,
+        WouldExceedMaxBlockCostLimit = 17,
+        UnsupportedVersion = 18,
+        InvalidWritableAccount = 19,
+        WouldExceedMaxAccountCostLimit = 20,
+        WouldExceedAccountDataBlockLimit = 21,
+        TooManyAccountLocks = 22,
+        AddressLookupTableNotFound = 23,
+        InvalidAddressLookupTableOwner = 24,
+        InvalidAddressLookupTableData = 25,
+        InvalidAddressLookupTableIndex = 26,
+        InvalidRentPayingAccount = 27,
+        WouldExceedMaxVoteCostLimit = 28,
+        WouldExceedAccountDataTotalLimit = 29,
+        DuplicateInstruction(index: u8) = 30,
+        InsufficientFundsForRent { account_index: u8 } = 31,
+        MaxLoadedAccountsDataSizeExceeded = 32,
+        InvalidLoadedAccountsDataSizeLimit = 33,
+        ResanitizationNeeded = 34,
+        ProgramExecutionTemporarilyRestricted { account_index: u8 } = 35,
+        UnbalancedTransaction = 36,
+        ProgramCacheHitMaxLimit = 37,
+        CommitCancelled = 38,
+        Unknown(details: String) = 39,
+    }
 }
 
-#[derive(
-    Debug,
-    Serialize,
-    Deserialize,
-    Clone,
-    PartialEq,
-    PartialOrd,
-    Ord,
-    Eq,
-    BorshSerialize,
-    BorshDeserialize,
-)]
+#[derive(Debug, Serialize, Clone, PartialEq, PartialOrd, Ord, Eq)]
 pub enum InstructionError {
     GenericError,
     InvalidArgument,
@@ -111,4 +153,556 @@ pub enum InstructionError {
     IllegalOwner,
     MaxAccountsDataSizeExceeded,
     ActiveVoteAccountClose,
+    BuiltinProgramsMustConsumeComputeUnits,
+}
+
+impl_stable_codes! {
+    InstructionError {
+        GenericError = 0,
+        InvalidArgument = 1,
+        InvalidInstructionData = 2,
+        InvalidAccountData = 3,
+        AccountDataTooSmall = 4,
+        InsufficientFunds = 5,
+        IncorrectProgramId = 6,
+        MissingRequiredSignature = 7,
+        AccountAlreadyInitialized = 8,
+        UninitializedAccount = 9,
+        UnbalancedInstruction = 10,
+        ModifiedProgramId = 11,
+        ExternalAccountLamportSpend = 12,
+        ExternalAccountDataModified = 13,
+        ReadonlyLamportChange = 14,
+        ReadonlyDataModified = 15,
+        DuplicateAccountIndex = 16,
+        ExecutableModified = 17,
+        RentEpochModified = 18,
+        NotEnoughAccountKeys = 19,
+        AccountDataSizeChanged = 20,
+        AccountNotExecutable = 21,
+        AccountBorrowFailed = 22,
+        AccountBorrowOutstanding = 23,
+        DuplicateAccountOutOfSync = 24,
+        Custom(code: u32) = 25,
+        InvalidError = 26,
+        ExecutableDataModified = 27,
+        ExecutableLamportChange = 28,
+        ExecutableAccountNotRentExempt = 29,
+        UnsupportedProgramId = 30,
+        CallDepth = 31,
+        MissingAccount = 32,
+        ReentrancyNotAllowed = 33,
+        MaxSeedLengthExceeded = 34,
+        InvalidSeeds = 35,
+        InvalidRealloc = 36,
+        ComputationalBudgetExceeded = 37,
+        PrivilegeEscalation = 38,
+        ProgramEnvironmentSetupFailure = 39,
+        ProgramFailedToComplete = 40,
+        ProgramFailedToCompile = 41,
+        Immutable = 42,
+        IncorrectAuthority = 43,
+        BorshIoError(details: String) = 44,
+        AccountNotRentExempt = 45,
+        InvalidAccountOwner = 46,
+        ArithmeticOverflow = 47,
+        UnsupportedSysvar = 48,
+        IllegalOwner = 49,
+        MaxAccountsDataSizeExceeded = 50,
+        ActiveVoteAccountClose = 51,
+        BuiltinProgramsMustConsumeComputeUnits = 52,
+    }
 }
+
+/// The variant names in declaration order, so the index of a name is also its
+/// serde variant index
+const INSTRUCTION_ERROR_VARIANTS: &[&str] = &[
+    "GenericError",
+    "InvalidArgument",
+    "InvalidInstructionData",
+    "InvalidAccountData",
+    "AccountDataTooSmall",
+    "InsufficientFunds",
+    "IncorrectProgramId",
+    "MissingRequiredSignature",
+    "AccountAlreadyInitialized",
+    "UninitializedAccount",
+    "UnbalancedInstruction",
+    "ModifiedProgramId",
+    "ExternalAccountLamportSpend",
+    "ExternalAccountDataModified",
+    "ReadonlyLamportChange",
+    "ReadonlyDataModified",
+    "DuplicateAccountIndex",
+    "ExecutableModified",
+    "RentEpochModified",
+    "NotEnoughAccountKeys",
+    "AccountDataSizeChanged",
+    "AccountNotExecutable",
+    "AccountBorrowFailed",
+    "AccountBorrowOutstanding",
+    "DuplicateAccountOutOfSync",
+    "Custom",
+    "InvalidError",
+    "ExecutableDataModified",
+    "ExecutableLamportChange",
+    "ExecutableAccountNotRentExempt",
+    "UnsupportedProgramId",
+    "CallDepth",
+    "MissingAccount",
+    "ReentrancyNotAllowed",
+    "MaxSeedLengthExceeded",
+    "InvalidSeeds",
+    "InvalidRealloc",
+    "ComputationalBudgetExceeded",
+    "PrivilegeEscalation",
+    "ProgramEnvironmentSetupFailure",
+    "ProgramFailedToComplete",
+    "ProgramFailedToCompile",
+    "Immutable",
+    "IncorrectAuthority",
+    "BorshIoError",
+    "AccountNotRentExempt",
+    "InvalidAccountOwner",
+    "ArithmeticOverflow",
+    "UnsupportedSysvar",
+    "IllegalOwner",
+    "MaxAccountsDataSizeExceeded",
+    "ActiveVoteAccountClose",
+    "BuiltinProgramsMustConsumeComputeUnits",
+];
+
+impl InstructionError {
+    fn from_unit_variant_name(name: &str) -> Option<Self> {
+        let error = match name {
+            "GenericError" => InstructionError::GenericError,
+            "InvalidArgument" => InstructionError::InvalidArgument,
+            "InvalidInstructionData" => InstructionError::InvalidInstructionData,
+            "InvalidAccountData" => InstructionError::InvalidAccountData,
+            "AccountDataTooSmall" => InstructionError::AccountDataTooSmall,
+            "InsufficientFunds" => InstructionError::InsufficientFunds,
+            "IncorrectProgramId" => InstructionError::IncorrectProgramId,
+            "MissingRequiredSignature" => InstructionError::MissingRequiredSignature,
+            "AccountAlreadyInitialized" => InstructionError::AccountAlreadyInitialized,
+            "UninitializedAccount" => InstructionError::UninitializedAccount,
+            "UnbalancedInstruction" => InstructionError::UnbalancedInstruction,
+            "ModifiedProgramId" => InstructionError::ModifiedProgramId,
+            "ExternalAccountLamportSpend" => InstructionError::ExternalAccountLamportSpend,
+            "ExternalAccountDataModified" => InstructionError::ExternalAccountDataModified,
+            "ReadonlyLamportChange" => InstructionError::ReadonlyLamportChange,
+            "ReadonlyDataModified" => InstructionError::ReadonlyDataModified,
+            "DuplicateAccountIndex" => InstructionError::DuplicateAccountIndex,
+            "ExecutableModified" => InstructionError::ExecutableModified,
+            "RentEpochModified" => InstructionError::RentEpochModified,
+            "NotEnoughAccountKeys" => InstructionError::NotEnoughAccountKeys,
+            "AccountDataSizeChanged" => InstructionError::AccountDataSizeChanged,
+            "AccountNotExecutable" => InstructionError::AccountNotExecutable,
+            "AccountBorrowFailed" => InstructionError::AccountBorrowFailed,
+            "AccountBorrowOutstanding" => InstructionError::AccountBorrowOutstanding,
+            "DuplicateAccountOutOfSync" => InstructionError::DuplicateAccountOutOfSync,
+            "InvalidError" => InstructionError::InvalidError,
+            "ExecutableDataModified" => InstructionError::ExecutableDataModified,
+            "ExecutableLamportChange" => InstructionError::ExecutableLamportChange,
+            "ExecutableAccountNotRentExempt" => InstructionError::ExecutableAccountNotRentExempt,
+            "UnsupportedProgramId" => InstructionError::UnsupportedProgramId,
+            "CallDepth" => InstructionError::CallDepth,
+            "MissingAccount" => InstructionError::MissingAccount,
+            "ReentrancyNotAllowed" => InstructionError::ReentrancyNotAllowed,
+            "MaxSeedLengthExceeded" => InstructionError::MaxSeedLengthExceeded,
+            "InvalidSeeds" => InstructionError::InvalidSeeds,
+            "InvalidRealloc" => InstructionError::InvalidRealloc,
+            "ComputationalBudgetExceeded" => InstructionError::ComputationalBudgetExceeded,
+            "PrivilegeEscalation" => InstructionError::PrivilegeEscalation,
+            "ProgramEnvironmentSetupFailure" => InstructionError::ProgramEnvironmentSetupFailure,
+            "ProgramFailedToComplete" => InstructionError::ProgramFailedToComplete,
+            "ProgramFailedToCompile" => InstructionError::ProgramFailedToCompile,
+            "Immutable" => InstructionError::Immutable,
+            "IncorrectAuthority" => InstructionError::IncorrectAuthority,
+            "AccountNotRentExempt" => InstructionError::AccountNotRentExempt,
+            "InvalidAccountOwner" => InstructionError::InvalidAccountOwner,
+            "ArithmeticOverflow" => InstructionError::ArithmeticOverflow,
+            "UnsupportedSysvar" => InstructionError::UnsupportedSysvar,
+            "IllegalOwner" => InstructionError::IllegalOwner,
+            "MaxAccountsDataSizeExceeded" => InstructionError::MaxAccountsDataSizeExceeded,
+            "ActiveVoteAccountClose" => InstructionError::ActiveVoteAccountClose,
+            "BuiltinProgramsMustConsumeComputeUnits" => {
+                InstructionError::BuiltinProgramsMustConsumeComputeUnits
+            }
+            // Renamed by newer Solana versions
+            "MaxAccountsDataAllocationsExceeded" => InstructionError::MaxAccountsDataSizeExceeded,
+            _ => return None,
+        };
+
+        Some(error)
+    }
+}
+
+/// Accepts the JSON Solana nodes return, where `BorshIoError` is
+/// `{"BorshIoError": "..."}` on older versions and a bare `"BorshIoError"`
+/// on newer ones. Non human readable formats use the regular enum encoding.
+impl<'de> Deserialize<'de> for InstructionError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(InstructionErrorVisitor)
+        } else {
+            deserializer.deserialize_enum(
+                "InstructionError",
+                INSTRUCTION_ERROR_VARIANTS,
+                InstructionErrorVisitor,
+            )
+        }
+    }
+}
+
+struct InstructionErrorVisitor;
+
+impl<'de> Visitor<'de> for InstructionErrorVisitor {
+    type Value = InstructionError;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a Solana instruction error")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        match name {
+            "BorshIoError" => Ok(InstructionError::BorshIoError(String::new())),
+            name => InstructionError::from_unit_variant_name(name)
+                .ok_or_else(|| E::unknown_variant(name, INSTRUCTION_ERROR_VARIANTS)),
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let name: String = map
+            .next_key()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+
+        let error = match name.as_str() {
+            "Custom" => InstructionError::Custom(map.next_value()?),
+            "BorshIoError" => InstructionError::BorshIoError(map.next_value()?),
+            name => {
+                map.next_value::<de::IgnoredAny>()?;
+                InstructionError::from_unit_variant_name(name)
+                    .ok_or_else(|| de::Error::unknown_variant(name, INSTRUCTION_ERROR_VARIANTS))?
+            }
+        };
+
+        if map.next_key::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+
+        Ok(error)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (VariantName(name), variant) = data.variant()?;
+
+        match name {
+            "Custom" => Ok(InstructionError::Custom(variant.newtype_variant()?)),
+            "BorshIoError" => Ok(InstructionError::BorshIoError(variant.newtype_variant()?)),
+            name => {
+                variant.unit_variant()?;
+                InstructionError::from_unit_variant_name(name)
+                    .ok_or_else(|| de::Error::unknown_variant(name, INSTRUCTION_ERROR_VARIANTS))
+            }
+        }
+    }
+}
+
+/// A variant identifier given either by name or by index
+struct VariantName(&'static str);
+
+impl<'de> Deserialize<'de> for VariantName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct VariantNameVisitor;
+
+        impl<'de> Visitor<'de> for VariantNameVisitor {
+            type Value = VariantName;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an instruction error variant")
+            }
+
+            fn visit_u64<E: de::Error>(self, index: u64) -> Result<Self::Value, E> {
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|index| INSTRUCTION_ERROR_VARIANTS.get(index))
+                    .map(|name| VariantName(name))
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(index), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+                INSTRUCTION_ERROR_VARIANTS
+                    .iter()
+                    .find(|variant| **variant == name)
+                    .map(|name| VariantName(name))
+                    .ok_or_else(|| E::unknown_variant(name, INSTRUCTION_ERROR_VARIANTS))
+            }
+        }
+
+        deserializer.deserialize_identifier(VariantNameVisitor)
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::AccountInUse => f.write_str("Account in use"),
+            TransactionError::AccountLoadedTwice => f.write_str("Account loaded twice"),
+            TransactionError::AccountNotFound => {
+                f.write_str("Attempt to debit an account but found no record of a prior credit.")
+            }
+            TransactionError::ProgramAccountNotFound => {
+                f.write_str("Attempt to load a program that does not exist")
+            }
+            TransactionError::InsufficientFundsForFee => f.write_str("Insufficient funds for fee"),
+            TransactionError::InvalidAccountForFee => {
+                f.write_str("This account may not be used to pay transaction fees")
+            }
+            TransactionError::AlreadyProcessed => {
+                f.write_str("This transaction has already been processed")
+            }
+            TransactionError::BlockhashNotFound => f.write_str("Blockhash not found"),
+            TransactionError::InstructionError(index, error) => {
+                write!(f, "Error processing Instruction {}: {}", index, error)
+            }
+            TransactionError::CallChainTooDeep => f.write_str("Loader call chain is too deep"),
+            TransactionError::MissingSignatureForFee => {
+                f.write_str("Transaction requires a fee but has no signature present")
+            }
+            TransactionError::InvalidAccountIndex => {
+                f.write_str("Transaction contains an invalid account reference")
+            }
+            TransactionError::SignatureFailure => {
+                f.write_str("Transaction did not pass signature verification")
+            }
+            TransactionError::InvalidProgramForExecution => {
+                f.write_str("This program may not be used for executing instructions")
+            }
+            TransactionError::SanitizeFailure => {
+                f.write_str("Transaction failed to sanitize accounts offsets correctly")
+            }
+            TransactionError::ClusterMaintenance => {
+                f.write_str("Transactions are currently disabled due to cluster maintenance")
+            }
+            TransactionError::AccountBorrowOutstanding => f.write_str(
+                "Transaction processing left an account with an outstanding borrowed reference",
+            ),
+            TransactionError::WouldExceedMaxBlockCostLimit => {
+                f.write_str("Transaction would exceed max Block Cost Limit")
+            }
+            TransactionError::UnsupportedVersion => {
+                f.write_str("Transaction version is unsupported")
+            }
+            TransactionError::InvalidWritableAccount => {
+                f.write_str("Transaction loads a writable account that cannot be written")
+            }
+            TransactionError::WouldExceedMaxAccountCostLimit => {
+                f.write_str("Transaction would exceed max account limit within the block")
+            }
+            TransactionError::WouldExceedAccountDataBlockLimit => {
+                f.write_str("Transaction would exceed account data limit within the block")
+            }
+            TransactionError::TooManyAccountLocks => {
+                f.write_str("Transaction locked too many accounts")
+            }
+            TransactionError::AddressLookupTableNotFound => {
+                f.write_str("Transaction loads an address table account that doesn't exist")
+            }
+            TransactionError::InvalidAddressLookupTableOwner => {
+                f.write_str("Transaction loads an address table account with an invalid owner")
+            }
+            TransactionError::InvalidAddressLookupTableData => {
+                f.write_str("Transaction loads an address table account with invalid data")
+            }
+            TransactionError::InvalidAddressLookupTableIndex => {
+                f.write_str("Transaction address table lookup uses an invalid index")
+            }
+            TransactionError::InvalidRentPayingAccount => f.write_str(
+                "Transaction leaves an account with a lower balance than rent-exempt minimum",
+            ),
+            TransactionError::WouldExceedMaxVoteCostLimit => {
+                f.write_str("Transaction would exceed max Vote Cost Limit")
+            }
+            TransactionError::WouldExceedAccountDataTotalLimit => {
+                f.write_str("Transaction would exceed total account data limit")
+            }
+            TransactionError::DuplicateInstruction(index) => write!(
+                f,
+                "Transaction contains a duplicate instruction ({}) that is not allowed",
+                index
+            ),
+            TransactionError::InsufficientFundsForRent { account_index } => write!(
+                f,
+                "Transaction results in an account ({}) with insufficient funds for rent",
+                account_index
+            ),
+            TransactionError::MaxLoadedAccountsDataSizeExceeded => {
+                f.write_str("Transaction exceeded max loaded accounts data size cap")
+            }
+            TransactionError::InvalidLoadedAccountsDataSizeLimit => f.write_str(
+                "LoadedAccountsDataSizeLimit set for transaction must be greater than 0.",
+            ),
+            TransactionError::ResanitizationNeeded => f.write_str(
+                "Sanitized transaction differed before/after feature activation. Needs to be resanitized.",
+            ),
+            TransactionError::ProgramExecutionTemporarilyRestricted { account_index } => write!(
+                f,
+                "Execution of the program referenced by account at index {} is temporarily restricted.",
+                account_index
+            ),
+            TransactionError::UnbalancedTransaction => {
+                f.write_str("Sum of account balances before and after transaction do not match")
+            }
+            TransactionError::ProgramCacheHitMaxLimit => f.write_str("Program cache hit max limit"),
+            TransactionError::CommitCancelled => f.write_str("CommitCancelled"),
+            TransactionError::Unknown(json) => write!(f, "unknown transaction error: {}", json),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransactionError::InstructionError(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstructionError::GenericError => f.write_str("generic instruction error"),
+            InstructionError::InvalidArgument => f.write_str("invalid program argument"),
+            InstructionError::InvalidInstructionData => f.write_str("invalid instruction data"),
+            InstructionError::InvalidAccountData => {
+                f.write_str("invalid account data for instruction")
+            }
+            InstructionError::AccountDataTooSmall => {
+                f.write_str("account data too small for instruction")
+            }
+            InstructionError::InsufficientFunds => {
+                f.write_str("insufficient funds for instruction")
+            }
+            InstructionError::IncorrectProgramId => {
+                f.write_str("incorrect program id for instruction")
+            }
+            InstructionError::MissingRequiredSignature => {
+                f.write_str("missing required signature for instruction")
+            }
+            InstructionError::AccountAlreadyInitialized => {
+                f.write_str("instruction requires an uninitialized account")
+            }
+            InstructionError::UninitializedAccount => {
+                f.write_str("instruction requires an initialized account")
+            }
+            InstructionError::UnbalancedInstruction => {
+                f.write_str("sum of account balances before and after instruction do not match")
+            }
+            InstructionError::ModifiedProgramId => {
+                f.write_str("instruction illegally modified the program id of an account")
+            }
+            InstructionError::ExternalAccountLamportSpend => {
+                f.write_str("instruction spent from the balance of an account it does not own")
+            }
+            InstructionError::ExternalAccountDataModified => {
+                f.write_str("instruction modified data of an account it does not own")
+            }
+            InstructionError::ReadonlyLamportChange => {
+                f.write_str("instruction changed the balance of a read-only account")
+            }
+            InstructionError::ReadonlyDataModified => {
+                f.write_str("instruction modified data of a read-only account")
+            }
+            InstructionError::DuplicateAccountIndex => {
+                f.write_str("instruction contains duplicate accounts")
+            }
+            InstructionError::ExecutableModified => {
+                f.write_str("instruction changed executable bit of an account")
+            }
+            InstructionError::RentEpochModified => {
+                f.write_str("instruction modified rent epoch of an account")
+            }
+            InstructionError::NotEnoughAccountKeys => {
+                f.write_str("insufficient account keys for instruction")
+            }
+            InstructionError::AccountDataSizeChanged => {
+                f.write_str("non-system instruction changed account size")
+            }
+            InstructionError::AccountNotExecutable => {
+                f.write_str("instruction expected an executable account")
+            }
+            InstructionError::AccountBorrowFailed => f.write_str(
+                "instruction tries to borrow reference for an account which is already borrowed",
+            ),
+            InstructionError::AccountBorrowOutstanding => {
+                f.write_str("instruction left account with an outstanding borrowed reference")
+            }
+            InstructionError::DuplicateAccountOutOfSync => {
+                f.write_str("instruction modifications of multiply-passed account differ")
+            }
+            InstructionError::Custom(code) => write!(f, "custom program error: {:#x}", code),
+            InstructionError::InvalidError => f.write_str("program returned invalid error code"),
+            InstructionError::ExecutableDataModified => {
+                f.write_str("instruction changed executable accounts data")
+            }
+            InstructionError::ExecutableLamportChange => {
+                f.write_str("instruction changed the balance of an executable account")
+            }
+            InstructionError::ExecutableAccountNotRentExempt => {
+                f.write_str("executable accounts must be rent exempt")
+            }
+            InstructionError::UnsupportedProgramId => f.write_str("Unsupported program id"),
+            InstructionError::CallDepth => {
+                f.write_str("Cross-program invocation call depth too deep")
+            }
+            InstructionError::MissingAccount => {
+                f.write_str("An account required by the instruction is missing")
+            }
+            InstructionError::ReentrancyNotAllowed => {
+                f.write_str("Cross-program invocation reentrancy not allowed for this instruction")
+            }
+            InstructionError::MaxSeedLengthExceeded => {
+                f.write_str("Length of the seed is too long for address generation")
+            }
+            InstructionError::InvalidSeeds => {
+                f.write_str("Provided seeds do not result in a valid address")
+            }
+            InstructionError::InvalidRealloc => f.write_str("Failed to reallocate account data"),
+            InstructionError::ComputationalBudgetExceeded => {
+                f.write_str("Computational budget exceeded")
+            }
+            InstructionError::PrivilegeEscalation => {
+                f.write_str("Cross-program invocation with unauthorized signer or writable account")
+            }
+            InstructionError::ProgramEnvironmentSetupFailure => {
+                f.write_str("Failed to create program execution environment")
+            }
+            InstructionError::ProgramFailedToComplete => f.write_str("Program failed to complete"),
+            InstructionError::ProgramFailedToCompile => f.write_str("Program failed to compile"),
+            InstructionError::Immutable => f.write_str("Account is immutable"),
+            InstructionError::IncorrectAuthority => f.write_str("Incorrect authority provided"),
+            InstructionError::BorshIoError(details) => {
+                write!(f, "IO Error: {}", details)
+            }
+            InstructionError::AccountNotRentExempt => {
+                f.write_str("An account does not have enough lamports to be rent-exempt")
+            }
+            InstructionError::InvalidAccountOwner => f.write_str("Invalid account owner"),
+            InstructionError::ArithmeticOverflow => f.write_str("Program arithmetic overflowed"),
+            InstructionError::UnsupportedSysvar => f.write_str("Unsupported sysvar"),
+            InstructionError::IllegalOwner => f.write_str("Provided owner is not allowed"),
+            InstructionError::MaxAccountsDataSizeExceeded => {
+                f.write_str("Requested account data allocation exceeded the accounts data budget")
+            }
+            InstructionError::ActiveVoteAccountClose => f.write_str(
+                "Cannot close vote account unless it stopped voting at least one full epoch ago",
+            ),
+            InstructionError::BuiltinProgramsMustConsumeComputeUnits => {
+                f.write_str("Builtin programs must consume compute units")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InstructionError {}
@@ -0,0 +1,362 @@
+//! The borsh wire encoding of [PoseidonError].
+//!
+//! Deriving borsh would encode the variant index, which shifts whenever a
+//! variant is added or removed. Every variant instead has a stable code and
+//! is encoded as
+//!
+//! | Field   | Type      | Description                                        |
+//! |---------|-----------|----------------------------------------------------|
+//! | code    | `u32`     | The stable code of the variant                     |
+//! | message | `String`  | The [core::fmt::Display] output of the error       |
+//! | payload | `Vec<u8>` | The borsh encoding of the variant fields, if any   |
+//!
+//! A decoder that does not know the code, or cannot decode its payload,
+//! yields [PoseidonError::Unknown] with the code and message intact.
+//! [PoseidonError::Context] is encoded with its own code, 9001, and carries
+//! the wrapped error in its payload. Context nested deeper than
+//! [MAX_CONTEXT_DEPTH] decodes to [PoseidonError::Unknown].
+//!
+//! Codes are grouped by area and are never reused:
+//!
+//! | Range     | Area                                     |
+//! |-----------|------------------------------------------|
+//! | 1000-1099 | Keys, signatures and their encodings     |
+//! | 1100-1199 | Mnemonics and derivation paths           |
+//! | 1200-1299 | Keystores and encryption                 |
+//! | 2000-2099 | User data, chunks and mime types         |
+//! | 2100-2199 | Text, hex and JSON decoding              |
+//! | 3000-3099 | Filesystem, I/O and stores               |
+//! | 3100-3199 | Sled                                     |
+//! | 4000-4099 | Accounts and transactions                |
+//! | 4100-4199 | Clusters, TLS, HTTP and JSON-RPC         |
+//! | 9000-9099 | Unspecified and context                  |
+
+use crate::{JsonError, PoseidonError, PoseidonErrorKind, PoseidonIoError};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::io::{self, Write};
+
+/// The deepest nesting of [PoseidonError::Context] that is decoded. Context
+/// only nests when built by hand, so deeper payloads are crafted and are cut
+/// off before they exhaust the stack.
+pub const MAX_CONTEXT_DEPTH: usize = 8;
+
+/// The 3002 payload: kind, untagged raw OS code, message and path
+type LegacyIoError = (PoseidonErrorKind, Option<i32>, String, Option<String>);
+
+impl PoseidonError {
+    /// The stable code of the error, identical across versions and features.
+    /// Context is transparent, the code is the one of the root cause.
+    pub fn code(&self) -> u32 {
+        match self {
+            PoseidonError::Context { source, .. } => source.code(),
+            _ => self.wire_code(),
+        }
+    }
+
+    fn wire_code(&self) -> u32 {
+        match self {
+            PoseidonError::MissingEd25519PublicKey => 1000,
+            PoseidonError::MissingKeypair => 1001,
+            PoseidonError::MissingTxSignature => 1002,
+            PoseidonError::InvalidBase58Ed25519SecretKey => 1003,
+            PoseidonError::InvalidBase58Ed25519PublicKey => 1004,
+            PoseidonError::InvalidBase58Ed25519Signature => 1005,
+            PoseidonError::InvalidBase58Sha256Hash => 1006,
+            PoseidonError::InvalidEd25519PublicKey => 1007,
+            PoseidonError::SignatureVerificationFailed => 1008,
+            PoseidonError::InvalidEd25519PublicKeyHex => 1009,
+            PoseidonError::MnemonicInvalidWordCount(..) => 1100,
+            PoseidonError::MnemonicUnknownWord { .. } => 1101,
+            PoseidonError::MnemonicInvalidChecksum => 1102,
+            PoseidonError::InvalidDerivationPath(..) => 1103,
+            PoseidonError::KeystoreWrongPassword => 1200,
+            PoseidonError::KeystoreCorrupted(..) => 1201,
+            PoseidonError::KeystoreUnsupportedVersion(..) => 1202,
+            PoseidonError::InvalidEncryptedData => 1203,
+            PoseidonError::DecryptionFailed => 1204,
+            PoseidonError::DataIdMismatch => 2000,
+            PoseidonError::BorshDeserializationFailed(..) => 2001,
+            PoseidonError::InvalidChunkManifest(..) => 2002,
+            PoseidonError::ChunkOutOfOrder { .. } => 2003,
+            PoseidonError::ChunkIntegrityMismatch { .. } => 2004,
+            PoseidonError::ChunkStreamIncomplete => 2005,
+            PoseidonError::InvalidMimeType(..) => 2006,
+            PoseidonError::MimeTypeMismatch { .. } => 2007,
+            PoseidonError::UnsupportedEncoding(..) => 2008,
+            PoseidonError::InvalidEncodedData(..) => 2009,
+            PoseidonError::DecodedSizeLimitExceeded { .. } => 2010,
+            PoseidonError::InvalidUtf8(..) => 2100,
+            PoseidonError::InvalidByteToUtf8StringConversion => 2101,
+            PoseidonError::InvalidHexCharacter { .. } => 2102,
+            PoseidonError::OddLength => 2103,
+            PoseidonError::InvalidStringLength => 2104,
+            PoseidonError::SerdeJson(..) => 2105,
+            PoseidonError::InvalidUtf8Sequence { .. } => 2106,
+            PoseidonError::InvalidBase58Character { .. } => 2107,
+            PoseidonError::NonAsciiBase58Character { .. } => 2108,
+            PoseidonError::InvalidBase58Length => 2109,
+            PoseidonError::InvalidSliceLength => 2110,
+            PoseidonError::HomeDirectoryNotFound => 3000,
+            PoseidonError::PathIsNotValidUtf8 => 3001,
+            // Raw OS codes are tagged with their OS, which does not fit the
+            // 3002 payload
+            PoseidonError::IoErr(error) if error.raw_os_error.is_some() => 3007,
+            PoseidonError::IoErr(..) => 3002,
+            PoseidonError::RepoCreatePermissionDenied => 3003,
+            PoseidonError::RepoAlreadyExists => 3004,
+            PoseidonError::Store(..) => 3005,
+            PoseidonError::InvalidConfig(..) => 3006,
+            PoseidonError::SledCollectionNotFound(..) => 3100,
+            PoseidonError::SledUnsupported(..) => 3101,
+            PoseidonError::SledReportableBug(..) => 3102,
+            PoseidonError::SledCorruption(..) => 3103,
+            PoseidonError::AccountNotFound => 4000,
+            PoseidonError::UnableToDeserializeAccountInfo => 4001,
+            PoseidonError::UnableToSerializeTx => 4002,
+            PoseidonError::Tx(..) => 4003,
+            PoseidonError::Rustls(..) => 4100,
+            PoseidonError::Http(..) => 4101,
+            // The 4102 payload predates `data`, errors that carry it need
+            // their own code to keep that payload readable by older versions
+            PoseidonError::Json(error) if error.data.is_some() => 4105,
+            PoseidonError::Json(..) => 4102,
+            PoseidonError::InvalidCluster(..) => 4103,
+            PoseidonError::NoHealthyEndpoint => 4104,
+            PoseidonError::NodeUnhealthy(..) => 4106,
+            PoseidonError::Unspecified(..) => 9000,
+            PoseidonError::Unknown { code, .. } => *code,
+            PoseidonError::Context { .. } => 9001,
+        }
+    }
+
+    /// Rebuilds an error from its code alone. Only variants without fields
+    /// can be rebuilt, the others become [PoseidonError::Unknown].
+    pub(crate) fn from_code(code: u32, message: String) -> Self {
+        match PoseidonError::from_payload(code, &[], 0) {
+            Ok(Some(error)) => error,
+            Ok(None) | Err(_) => PoseidonError::Unknown { code, message },
+        }
+    }
+
+    /// The borsh encoding of the variant fields
+    fn payload(&self) -> io::Result<Vec<u8>> {
+        match self {
+            PoseidonError::MnemonicInvalidWordCount(count) => count.try_to_vec(),
+            PoseidonError::MnemonicUnknownWord { index } => index.try_to_vec(),
+            PoseidonError::InvalidDerivationPath(details)
+            | PoseidonError::KeystoreCorrupted(details)
+            | PoseidonError::BorshDeserializationFailed(details)
+            | PoseidonError::InvalidChunkManifest(details)
+            | PoseidonError::InvalidMimeType(details)
+            | PoseidonError::UnsupportedEncoding(details)
+            | PoseidonError::InvalidEncodedData(details)
+            | PoseidonError::InvalidUtf8(details)
+            | PoseidonError::SerdeJson(details)
+            | PoseidonError::InvalidCluster(details)
+            | PoseidonError::NodeUnhealthy(details)
+            | PoseidonError::InvalidConfig(details)
+            | PoseidonError::SledCollectionNotFound(details)
+            | PoseidonError::SledUnsupported(details)
+            | PoseidonError::SledReportableBug(details)
+            | PoseidonError::SledCorruption(details)
+            | PoseidonError::Unspecified(details) => details.try_to_vec(),
+            PoseidonError::KeystoreUnsupportedVersion(version) => version.try_to_vec(),
+            PoseidonError::ChunkOutOfOrder { expected, found } => (expected, found).try_to_vec(),
+            PoseidonError::ChunkIntegrityMismatch { index } => index.try_to_vec(),
+            PoseidonError::MimeTypeMismatch { declared, detected } => {
+                (declared, detected).try_to_vec()
+            }
+            PoseidonError::DecodedSizeLimitExceeded { limit } => limit.try_to_vec(),
+            PoseidonError::Context { context, source } => (context, source).try_to_vec(),
+            PoseidonError::InvalidHexCharacter { c, index }
+            | PoseidonError::InvalidBase58Character { c, index } => (c, index).try_to_vec(),
+            PoseidonError::InvalidUtf8Sequence {
+                valid_up_to,
+                error_len,
+            } => (valid_up_to, error_len).try_to_vec(),
+            PoseidonError::NonAsciiBase58Character { index } => index.try_to_vec(),
+            PoseidonError::IoErr(error) => error.try_to_vec(),
+            PoseidonError::Store(error) => error.try_to_vec(),
+            PoseidonError::Tx(error) => error.try_to_vec(),
+            PoseidonError::Rustls(error) => error.try_to_vec(),
+            PoseidonError::Http(error) => error.try_to_vec(),
+            PoseidonError::Json(error) if error.data.is_some() => error.try_to_vec(),
+            PoseidonError::Json(error) => (error.code, &error.message).try_to_vec(),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Rebuilds the variant for `code` from its payload, `None` if the code
+    /// is not known to this build. `depth` is the number of context layers
+    /// around the error.
+    fn from_payload(code: u32, payload: &[u8], depth: usize) -> io::Result<Option<Self>> {
+        fn decode<T: BorshDeserialize>(payload: &[u8]) -> io::Result<T> {
+            T::try_from_slice(payload)
+        }
+
+        let error = match code {
+            1000 => PoseidonError::MissingEd25519PublicKey,
+            1001 => PoseidonError::MissingKeypair,
+            1002 => PoseidonError::MissingTxSignature,
+            1003 => PoseidonError::InvalidBase58Ed25519SecretKey,
+            1004 => PoseidonError::InvalidBase58Ed25519PublicKey,
+            1005 => PoseidonError::InvalidBase58Ed25519Signature,
+            1006 => PoseidonError::InvalidBase58Sha256Hash,
+            1007 => PoseidonError::InvalidEd25519PublicKey,
+            1008 => PoseidonError::SignatureVerificationFailed,
+            1009 => PoseidonError::InvalidEd25519PublicKeyHex,
+            1100 => PoseidonError::MnemonicInvalidWordCount(decode(payload)?),
+            1101 => PoseidonError::MnemonicUnknownWord {
+                index: decode(payload)?,
+            },
+            1102 => PoseidonError::MnemonicInvalidChecksum,
+            1103 => PoseidonError::InvalidDerivationPath(decode(payload)?),
+            1200 => PoseidonError::KeystoreWrongPassword,
+            1201 => PoseidonError::KeystoreCorrupted(decode(payload)?),
+            1202 => PoseidonError::KeystoreUnsupportedVersion(decode(payload)?),
+            1203 => PoseidonError::InvalidEncryptedData,
+            1204 => PoseidonError::DecryptionFailed,
+            2000 => PoseidonError::DataIdMismatch,
+            2001 => PoseidonError::BorshDeserializationFailed(decode(payload)?),
+            2002 => PoseidonError::InvalidChunkManifest(decode(payload)?),
+            2003 => {
+                let (expected, found) = decode(payload)?;
+                PoseidonError::ChunkOutOfOrder { expected, found }
+            }
+            2004 => PoseidonError::ChunkIntegrityMismatch {
+                index: decode(payload)?,
+            },
+            2005 => PoseidonError::ChunkStreamIncomplete,
+            2006 => PoseidonError::InvalidMimeType(decode(payload)?),
+            2007 => {
+                let (declared, detected) = decode(payload)?;
+                PoseidonError::MimeTypeMismatch { declared, detected }
+            }
+            2008 => PoseidonError::UnsupportedEncoding(decode(payload)?),
+            2009 => PoseidonError::InvalidEncodedData(decode(payload)?),
+            2010 => PoseidonError::DecodedSizeLimitExceeded {
+                limit: decode(payload)?,
+            },
+            2100 => PoseidonError::InvalidUtf8(decode(payload)?),
+            2101 => PoseidonError::InvalidByteToUtf8StringConversion,
+            2102 => {
+                let (c, index) = decode(payload)?;
+                PoseidonError::InvalidHexCharacter { c, index }
+            }
+            2103 => PoseidonError::OddLength,
+            2104 => PoseidonError::InvalidStringLength,
+            2105 => PoseidonError::SerdeJson(decode(payload)?),
+            2106 => {
+                let (valid_up_to, error_len) = decode(payload)?;
+                PoseidonError::InvalidUtf8Sequence {
+                    valid_up_to,
+                    error_len,
+                }
+            }
+            2107 => {
+                let (c, index) = decode(payload)?;
+                PoseidonError::InvalidBase58Character { c, index }
+            }
+            2108 => PoseidonError::NonAsciiBase58Character {
+                index: decode(payload)?,
+            },
+            2109 => PoseidonError::InvalidBase58Length,
+            2110 => PoseidonError::InvalidSliceLength,
+            3000 => PoseidonError::HomeDirectoryNotFound,
+            3001 => PoseidonError::PathIsNotValidUtf8,
+            // Older versions only sent the kind. A raw OS code sent without its
+            // OS cannot be trusted and is dropped, the message still has it.
+            3002 => PoseidonError::IoErr(
+                decode::<LegacyIoError>(payload)
+                    .map(|(kind, _, message, path)| PoseidonIoError {
+                        kind,
+                        raw_os_error: None,
+                        message,
+                        path,
+                    })
+                    .or_else(|_| decode::<PoseidonErrorKind>(payload).map(PoseidonIoError::from))?,
+            ),
+            3003 => PoseidonError::RepoCreatePermissionDenied,
+            3004 => PoseidonError::RepoAlreadyExists,
+            3005 => PoseidonError::Store(decode(payload)?),
+            3006 => PoseidonError::InvalidConfig(decode(payload)?),
+            3007 => PoseidonError::IoErr(decode(payload)?),
+            3100 => PoseidonError::SledCollectionNotFound(decode(payload)?),
+            3101 => PoseidonError::SledUnsupported(decode(payload)?),
+            3102 => PoseidonError::SledReportableBug(decode(payload)?),
+            3103 => PoseidonError::SledCorruption(decode(payload)?),
+            4000 => PoseidonError::AccountNotFound,
+            4001 => PoseidonError::UnableToDeserializeAccountInfo,
+            4002 => PoseidonError::UnableToSerializeTx,
+            4003 => PoseidonError::Tx(decode(payload)?),
+            4100 => PoseidonError::Rustls(decode(payload)?),
+            4101 => PoseidonError::Http(decode(payload)?),
+            4102 => {
+                let (code, message) = decode(payload)?;
+                PoseidonError::Json(JsonError {
+                    code,
+                    message,
+                    data: None,
+                })
+            }
+            4103 => PoseidonError::InvalidCluster(decode(payload)?),
+            4104 => PoseidonError::NoHealthyEndpoint,
+            4105 => PoseidonError::Json(decode(payload)?),
+            4106 => PoseidonError::NodeUnhealthy(decode(payload)?),
+            9000 => PoseidonError::Unspecified(decode(payload)?),
+            9001 if depth < MAX_CONTEXT_DEPTH => {
+                let mut buf = payload;
+                let context = Vec::<String>::deserialize(&mut buf)?;
+                let source = PoseidonError::decode(&mut buf, depth + 1)?;
+                if !buf.is_empty() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "trailing bytes after the context source",
+                    ));
+                }
+
+                PoseidonError::Context {
+                    context,
+                    source: Box::new(source),
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(error))
+    }
+}
+
+impl BorshSerialize for PoseidonError {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let message = match self {
+            PoseidonError::Unknown { message, .. } => message.clone(),
+            _ => self.to_string(),
+        };
+
+        self.wire_code().serialize(writer)?;
+        message.serialize(writer)?;
+        self.payload()?.serialize(writer)
+    }
+}
+
+impl PoseidonError {
+    fn decode(buf: &mut &[u8], depth: usize) -> io::Result<Self> {
+        let code = u32::deserialize(buf)?;
+        let message = String::deserialize(buf)?;
+        let payload = Vec::<u8>::deserialize(buf)?;
+
+        // A payload that fails to decode was written by a version with a
+        // newer layout for the variant, so it degrades like an unknown code
+        match PoseidonError::from_payload(code, &payload, depth) {
+            Ok(Some(error)) => Ok(error),
+            Ok(None) | Err(_) => Ok(PoseidonError::Unknown { code, message }),
+        }
+    }
+}
+
+impl BorshDeserialize for PoseidonError {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        PoseidonError::decode(buf, 0)
+    }
+}
@@ -0,0 +1,68 @@
+//! JSON-RPC 2.0 requests over HTTP
+
+use crate::{JsonError, PoseidonError, PoseidonResult};
+use core::time::Duration;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize)]
+struct Request<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    #[serde(default)]
+    result: Value,
+    error: Option<JsonError>,
+}
+
+/// Calls `method` on the node at `url`. JSON-RPC errors are returned as
+/// [JsonError]s, HTTP errors without a JSON-RPC body with
+/// [PoseidonError::from_http_status].
+pub(crate) fn call<R: DeserializeOwned>(
+    url: &str,
+    method: &str,
+    params: Value,
+    timeout: Duration,
+) -> PoseidonResult<R> {
+    let body = serde_json::to_vec(&Request {
+        jsonrpc: "2.0",
+        id: 1,
+        method,
+        params,
+    })?;
+
+    let response = minreq::post(url)
+        .with_header("Content-Type", "application/json")
+        .with_body(body)
+        // minreq only has whole second timeouts
+        .with_timeout(timeout.as_secs().max(1))
+        .send()?;
+
+    let parsed = serde_json::from_slice::<Response>(response.as_bytes());
+    match (parsed, response.status_code) {
+        (
+            Ok(Response {
+                error: Some(error), ..
+            }),
+            _,
+        ) => Err(error.into()),
+        (Ok(Response { result, .. }), 200..=299) => Ok(serde_json::from_value(result)?),
+        (parsed, status) => {
+            match PoseidonError::from_http_status(status as u16, response.reason_phrase) {
+                Some(error) => Err(error),
+                None => Err(parsed.err().map_or(
+                    PoseidonError::SerdeJson(
+                        "the response is neither a JSON-RPC result nor an error".to_owned(),
+                    ),
+                    PoseidonError::from,
+                )),
+            }
+        }
+    }
+}
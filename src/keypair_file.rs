@@ -0,0 +1,92 @@
+use crate::{
+    private_file::write_private_file, Ed25519Keypair, PoseidonError, PoseidonErrorKind,
+    PoseidonIoError, PoseidonResult,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroizing;
+
+/// The keypair location used by the Solana CLI, relative to the home directory
+pub const DEFAULT_KEYPAIR_PATH: &str = ".config/solana/id.json";
+
+/// Resolves `~/.config/solana/id.json` for the current user
+pub fn default_keypair_path() -> PoseidonResult<PathBuf> {
+    let home = dirs::home_dir().ok_or(PoseidonError::HomeDirectoryNotFound)?;
+
+    Ok(home.join(DEFAULT_KEYPAIR_PATH))
+}
+
+/// Expands a leading `~` in `path` to the current user's home directory
+pub fn expand_home_path<P: AsRef<Path>>(path: P) -> PoseidonResult<PathBuf> {
+    let path = path.as_ref();
+    let path_str = path.to_str().ok_or(PoseidonError::PathIsNotValidUtf8)?;
+
+    if path_str == "~" || path_str.starts_with("~/") {
+        let home = dirs::home_dir().ok_or(PoseidonError::HomeDirectoryNotFound)?;
+
+        Ok(home.join(path_str.trim_start_matches('~').trim_start_matches('/')))
+    } else {
+        Ok(path.to_path_buf())
+    }
+}
+
+/// Resolves the location of a keypair file, see [expand_home_path]
+pub fn resolve_keypair_path<P: AsRef<Path>>(path: P) -> PoseidonResult<PathBuf> {
+    expand_home_path(path)
+}
+
+/// Parses the JSON array of 64 bytes written by `solana-keygen`
+pub fn keypair_from_json(json: &str) -> PoseidonResult<Ed25519Keypair> {
+    let bytes: Zeroizing<Vec<u8>> = Zeroizing::new(serde_json::from_str(json)?);
+
+    Ed25519Keypair::from_bytes(&bytes)
+}
+
+/// Encodes the keypair as the JSON array of 64 bytes used by `solana-keygen`
+pub fn keypair_to_json(keypair: &Ed25519Keypair) -> PoseidonResult<Zeroizing<String>> {
+    Ok(Zeroizing::new(serde_json::to_string(
+        keypair.to_bytes().as_slice(),
+    )?))
+}
+
+pub fn read_keypair_file<P: AsRef<Path>>(path: P) -> PoseidonResult<Ed25519Keypair> {
+    let path = resolve_keypair_path(path)?;
+    let json = Zeroizing::new(
+        fs::read_to_string(&path).map_err(|error| PoseidonIoError::from(error).with_path(&path))?,
+    );
+
+    keypair_from_json(&json)
+}
+
+/// Reads the keypair at [default_keypair_path], returning
+/// [PoseidonError::MissingKeypair] if no keypair has been created yet.
+pub fn read_default_keypair() -> PoseidonResult<Ed25519Keypair> {
+    let path = default_keypair_path()?;
+
+    match read_keypair_file(path) {
+        Err(PoseidonError::IoErr(error)) if error.kind == PoseidonErrorKind::NotFound => {
+            Err(PoseidonError::MissingKeypair)
+        }
+        outcome => outcome,
+    }
+}
+
+/// Writes the keypair to `path`, creating missing parent directories.
+/// On unix the file is only readable and writable by its owner (`0600`).
+pub fn write_keypair_file<P: AsRef<Path>>(
+    keypair: &Ed25519Keypair,
+    path: P,
+) -> PoseidonResult<PathBuf> {
+    let path = resolve_keypair_path(path)?;
+
+    write_private_file(&path, keypair_to_json(keypair)?.as_bytes())?;
+
+    Ok(path)
+}
+
+/// Writes the keypair to [default_keypair_path]
+pub fn write_default_keypair(keypair: &Ed25519Keypair) -> PoseidonResult<PathBuf> {
+    write_keypair_file(keypair, default_keypair_path()?)
+}
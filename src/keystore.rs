@@ -0,0 +1,276 @@
+use crate::{
+    private_file::write_private_file, Ed25519Keypair, Ed25519PublicKey, PoseidonError,
+    PoseidonIoError, PoseidonResult,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use rand_core::{OsRng, RngCore};
+use std::{fs, path::Path};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+const KEYSTORE_MAGIC: [u8; 8] = *b"PSDNKEYS";
+const KEYSTORE_VERSION: u8 = 1;
+const KDF_SCRYPT: u8 = 1;
+
+// Upper bounds on the scrypt parameters accepted from a file so that a
+// corrupted or hostile keystore cannot make us allocate or compute without
+// limit. scrypt allocates `128 * r * 2^log_n` bytes and fills and reads them
+// back once per unit of `p`, so the work is bounded by the memory times `p`.
+// The defaults use 32MiB, the bounds allow 8 times the memory and 32 times
+// the work.
+const MAX_MEMORY: u64 = 256 * 1024 * 1024;
+const MAX_WORK: u64 = 4 * MAX_MEMORY;
+
+/// scrypt cost parameters stored in the keystore header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct KeystoreKdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl KeystoreKdfParams {
+    fn validate(&self) -> PoseidonResult<scrypt::Params> {
+        let memory = 1u64
+            .checked_shl(u32::from(self.log_n))
+            .and_then(|n| n.checked_mul(128 * u64::from(self.r)));
+        let work = memory.and_then(|memory| memory.checked_mul(u64::from(self.p)));
+
+        let within_limits = match (memory, work) {
+            (Some(memory), Some(work)) => memory <= MAX_MEMORY && work <= MAX_WORK,
+            _ => false,
+        };
+        if !within_limits {
+            return Err(PoseidonError::KeystoreCorrupted(format!(
+                "scrypt parameters `{:?}` exceed the supported limits",
+                self
+            )));
+        }
+
+        scrypt::Params::new(self.log_n, self.r, self.p, 64).map_err(|_| {
+            PoseidonError::KeystoreCorrupted(format!("invalid scrypt parameters `{:?}`", self))
+        })
+    }
+}
+
+/// `log_n = 15`, `r = 8`, `p = 1`, which takes roughly 100ms and 32MiB
+impl Default for KeystoreKdfParams {
+    fn default() -> Self {
+        KeystoreKdfParams {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/// The cleartext part of a keystore. It is authenticated as associated data
+/// so any modification makes decryption fail.
+///
+/// Layout (borsh, little endian):
+///
+/// | Field        | Size | Notes                                      |
+/// |--------------|------|--------------------------------------------|
+/// | `magic`      | 8    | `PSDNKEYS`                                 |
+/// | `version`    | 1    | `1`                                        |
+/// | `kdf`        | 1    | `1` for scrypt                             |
+/// | `kdf_params` | 9    | `log_n: u8`, `r: u32`, `p: u32`            |
+/// | `salt`       | 32   | scrypt salt                                |
+/// | `nonce`      | 24   | XChaCha20-Poly1305 nonce                   |
+/// | `public_key` | 32   | public key of the encrypted keypair        |
+/// | `key_check`  | 32   | second half of the scrypt output           |
+///
+/// The header is followed by the length prefixed XChaCha20-Poly1305
+/// ciphertext of the 32 byte secret seed, keyed with the first half of the
+/// scrypt output.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct KeystoreHeader {
+    pub magic: [u8; 8],
+    pub version: u8,
+    pub kdf: u8,
+    pub kdf_params: KeystoreKdfParams,
+    pub salt: [u8; 32],
+    pub nonce: [u8; 24],
+    pub public_key: Ed25519PublicKey,
+    pub key_check: [u8; 32],
+}
+
+/// A password encrypted [Ed25519Keypair]
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct EncryptedKeystore {
+    pub header: KeystoreHeader,
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedKeystore {
+    pub fn encrypt(keypair: &Ed25519Keypair, password: &[u8]) -> PoseidonResult<Self> {
+        Self::encrypt_with_params(keypair, password, KeystoreKdfParams::default())
+    }
+
+    pub fn encrypt_with_params(
+        keypair: &Ed25519Keypair,
+        password: &[u8],
+        kdf_params: KeystoreKdfParams,
+    ) -> PoseidonResult<Self> {
+        let mut salt = [0u8; 32];
+        let mut nonce = [0u8; 24];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let derived_key = derive_key(password, &salt, &kdf_params)?;
+        let mut key_check = [0u8; 32];
+        key_check.copy_from_slice(&derived_key[32..]);
+
+        let header = KeystoreHeader {
+            magic: KEYSTORE_MAGIC,
+            version: KEYSTORE_VERSION,
+            kdf: KDF_SCRYPT,
+            kdf_params,
+            salt,
+            nonce,
+            public_key: keypair.public_key(),
+            key_check,
+        };
+        let aad = header.try_to_vec()?;
+
+        let ciphertext = cipher(&derived_key)
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: keypair.seed().as_ref(),
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| PoseidonError::Unspecified("keystore encryption failed".to_owned()))?;
+
+        Ok(EncryptedKeystore { header, ciphertext })
+    }
+
+    /// Decrypts the keypair. Returns [PoseidonError::KeystoreWrongPassword]
+    /// if the password does not match and [PoseidonError::KeystoreCorrupted]
+    /// if the file was modified after it was written.
+    pub fn decrypt(&self, password: &[u8]) -> PoseidonResult<Ed25519Keypair> {
+        let header = &self.header;
+        header.check_format()?;
+
+        let derived_key = derive_key(password, &header.salt, &header.kdf_params)?;
+        if !bool::from(derived_key[32..].ct_eq(&header.key_check)) {
+            return Err(PoseidonError::KeystoreWrongPassword);
+        }
+
+        let aad = header.try_to_vec()?;
+        let seed = Zeroizing::new(
+            cipher(&derived_key)
+                .decrypt(
+                    XNonce::from_slice(&header.nonce),
+                    Payload {
+                        msg: &self.ciphertext,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| {
+                    PoseidonError::KeystoreCorrupted("ciphertext failed authentication".to_owned())
+                })?,
+        );
+        let seed: &[u8; 32] = seed.as_slice().try_into().map_err(|_| {
+            PoseidonError::KeystoreCorrupted("decrypted secret has the wrong length".to_owned())
+        })?;
+
+        let keypair = Ed25519Keypair::from_seed(seed);
+        if keypair.public_key() != header.public_key {
+            return Err(PoseidonError::KeystoreCorrupted(
+                "decrypted secret does not match the public key".to_owned(),
+            ));
+        }
+
+        Ok(keypair)
+    }
+
+    /// The public key of the encrypted keypair, readable without the password
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        self.header.public_key
+    }
+
+    pub fn to_bytes(&self) -> PoseidonResult<Vec<u8>> {
+        Ok(self.try_to_vec()?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> PoseidonResult<Self> {
+        let keystore = EncryptedKeystore::try_from_slice(bytes).map_err(|error| {
+            PoseidonError::KeystoreCorrupted(format!("malformed keystore - `{}`", error))
+        })?;
+        keystore.header.check_format()?;
+
+        Ok(keystore)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> PoseidonResult<Self> {
+        let path = path.as_ref();
+
+        Self::from_bytes(
+            &fs::read(path).map_err(|error| PoseidonIoError::from(error).with_path(path))?,
+        )
+    }
+
+    /// Writes the keystore to `path` with owner only (`0600`) permissions on unix
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> PoseidonResult<()> {
+        write_private_file(path.as_ref(), &self.to_bytes()?)
+    }
+}
+
+impl KeystoreHeader {
+    fn check_format(&self) -> PoseidonResult<()> {
+        if self.magic != KEYSTORE_MAGIC {
+            return Err(PoseidonError::KeystoreCorrupted(
+                "not a Poseidon keystore".to_owned(),
+            ));
+        }
+        if self.version != KEYSTORE_VERSION {
+            return Err(PoseidonError::KeystoreUnsupportedVersion(self.version));
+        }
+        if self.kdf != KDF_SCRYPT {
+            return Err(PoseidonError::KeystoreCorrupted(format!(
+                "unknown key derivation function `{}`",
+                self.kdf
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Encrypts `keypair` with `password` and writes it to `path`
+pub fn save_keystore<P: AsRef<Path>>(
+    keypair: &Ed25519Keypair,
+    password: &[u8],
+    path: P,
+) -> PoseidonResult<()> {
+    EncryptedKeystore::encrypt(keypair, password)?.save(path)
+}
+
+/// Reads the keystore at `path` and decrypts it with `password`
+pub fn load_keystore<P: AsRef<Path>>(path: P, password: &[u8]) -> PoseidonResult<Ed25519Keypair> {
+    EncryptedKeystore::load(path)?.decrypt(password)
+}
+
+fn derive_key(
+    password: &[u8],
+    salt: &[u8],
+    kdf_params: &KeystoreKdfParams,
+) -> PoseidonResult<Zeroizing<[u8; 64]>> {
+    let params = kdf_params.validate()?;
+    let mut derived_key = Zeroizing::new([0u8; 64]);
+
+    scrypt::scrypt(password, salt, &params, derived_key.as_mut())
+        .map_err(|_| PoseidonError::Unspecified("invalid scrypt output length".to_owned()))?;
+
+    Ok(derived_key)
+}
+
+fn cipher(derived_key: &[u8; 64]) -> XChaCha20Poly1305 {
+    XChaCha20Poly1305::new_from_slice(&derived_key[..32]).expect("key is 32 bytes long")
+}
@@ -3,10 +3,54 @@
 //! Commonly used types in the Poseidon libraries
 //!
 //!
+mod chunked;
+#[cfg(feature = "http")]
+mod cluster_pool;
 mod common;
+mod compression;
+#[cfg(feature = "config")]
+mod config;
+mod data_id;
+mod ed25519;
 mod errors;
+#[cfg(feature = "http")]
+mod json_rpc;
+#[cfg(feature = "keypair_file")]
+mod keypair_file;
+#[cfg(feature = "keystore")]
+mod keystore;
+mod mime;
+#[cfg(feature = "mnemonic")]
+mod mnemonic;
+#[cfg(any(feature = "keypair_file", feature = "keystore"))]
+mod private_file;
+mod program_errors;
 mod pss;
+#[cfg(feature = "http")]
+mod rpc_client;
+#[cfg(feature = "sealed_box")]
+mod sealed_box;
 
+pub use chunked::*;
+#[cfg(feature = "http")]
+pub use cluster_pool::*;
 pub use common::*;
+pub use compression::*;
+#[cfg(feature = "config")]
+pub use config::*;
+pub use data_id::*;
+pub use ed25519::*;
 pub use errors::*;
+#[cfg(feature = "keypair_file")]
+pub use keypair_file::*;
+#[cfg(feature = "keystore")]
+pub use keystore::*;
+pub use mime::*;
+#[cfg(feature = "mnemonic")]
+pub use mnemonic::*;
+pub use program_errors::*;
 pub use pss::*;
+#[cfg(feature = "http")]
+pub use rpc_client::*;
+#[cfg(feature = "sealed_box")]
+pub use sealed_box::*;
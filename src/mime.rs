@@ -0,0 +1,280 @@
+use crate::{PoseidonError, PoseidonResult, UserData};
+use core::{fmt, str::FromStr};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A media type such as `text/plain; charset=utf-8`, validated per RFC 6838.
+///
+/// The type, subtype and parameter names are case-insensitive and stored in
+/// lowercase, parameter values are kept as given.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MimeType {
+    top_level: String,
+    subtype: String,
+    parameters: Vec<(String, String)>,
+}
+
+impl MimeType {
+    pub fn top_level(&self) -> &str {
+        &self.top_level
+    }
+
+    pub fn subtype(&self) -> &str {
+        &self.subtype
+    }
+
+    pub fn parameters(&self) -> &[(String, String)] {
+        &self.parameters
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The `type/subtype` part without parameters
+    pub fn essence(&self) -> String {
+        format!("{}/{}", self.top_level, self.subtype)
+    }
+}
+
+impl FromStr for MimeType {
+    type Err = PoseidonError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            |reason: &str| PoseidonError::InvalidMimeType(format!("`{}` {}", value, reason));
+
+        let mut sections = split_unquoted(value, ';').into_iter();
+        let essence = sections.next().unwrap_or_default().trim();
+        let (top_level, subtype) = essence
+            .split_once('/')
+            .ok_or_else(|| invalid("is missing a `/` between type and subtype"))?;
+
+        if !is_restricted_name(top_level) {
+            return Err(invalid("has an invalid type"));
+        }
+        if !is_restricted_name(subtype) {
+            return Err(invalid("has an invalid subtype"));
+        }
+
+        // Empty parameters, e.g. after a trailing `;`, are skipped and
+        // whitespace around the `=` is allowed
+        let parameters = sections
+            .map(str::trim)
+            .filter(|parameter| !parameter.is_empty())
+            .map(|parameter| {
+                let (name, parameter_value) = parameter
+                    .split_once('=')
+                    .ok_or_else(|| invalid("has a parameter without a value"))?;
+                let (name, parameter_value) = (name.trim_end(), parameter_value.trim_start());
+
+                if !is_restricted_name(name) {
+                    return Err(invalid("has an invalid parameter name"));
+                }
+
+                let parameter_value = parse_parameter_value(parameter_value)
+                    .ok_or_else(|| invalid("has an invalid parameter value"))?;
+
+                Ok((name.to_ascii_lowercase(), parameter_value))
+            })
+            .collect::<PoseidonResult<Vec<(String, String)>>>()?;
+
+        Ok(MimeType {
+            top_level: top_level.to_ascii_lowercase(),
+            subtype: subtype.to_ascii_lowercase(),
+            parameters,
+        })
+    }
+}
+
+impl fmt::Display for MimeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.top_level, self.subtype)?;
+
+        for (name, value) in &self.parameters {
+            if !value.is_empty() && value.bytes().all(is_token_byte) {
+                write!(f, "; {}={}", name, value)?;
+            } else {
+                write!(f, "; {}=\"", name)?;
+                for character in value.chars() {
+                    if character == '"' || character == '\\' {
+                        f.write_str("\\")?;
+                    }
+                    write!(f, "{}", character)?;
+                }
+                f.write_str("\"")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for MimeType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MimeType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        value
+            .parse()
+            .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&value), &"a media type"))
+    }
+}
+
+/// `restricted-name` from RFC 6838 section 4.2
+fn is_restricted_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+
+    !bytes.is_empty()
+        && bytes.len() <= 127
+        && bytes[0].is_ascii_alphanumeric()
+        && bytes[1..].iter().all(|byte| {
+            byte.is_ascii_alphanumeric()
+                || matches!(
+                    byte,
+                    b'!' | b'#' | b'$' | b'&' | b'-' | b'^' | b'_' | b'.' | b'+'
+                )
+        })
+}
+
+/// Splits `value` on `separator`, ignoring separators inside quoted strings
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (index, character) in value.char_indices() {
+        match character {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            character if character == separator && !quoted => {
+                sections.push(&value[start..index]);
+                start = index + character.len_utf8();
+            }
+            _ => (),
+        }
+    }
+    sections.push(&value[start..]);
+
+    sections
+}
+
+/// `token` from RFC 2045 section 5.1
+fn is_token_byte(byte: u8) -> bool {
+    byte.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?=".contains(&byte)
+}
+
+fn parse_parameter_value(value: &str) -> Option<String> {
+    if let Some(quoted) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        let mut unquoted = String::with_capacity(quoted.len());
+        let mut characters = quoted.chars();
+
+        while let Some(character) = characters.next() {
+            match character {
+                '\\' => unquoted.push(characters.next()?),
+                '"' => return None,
+                character if character.is_ascii_control() => return None,
+                character => unquoted.push(character),
+            }
+        }
+
+        Some(unquoted)
+    } else if !value.is_empty() && value.bytes().all(is_token_byte) {
+        Some(value.to_owned())
+    } else {
+        None
+    }
+}
+
+/// The `(offset, bytes)` parts of a magic byte signature, all of which must
+/// match
+type Signature = &'static [(usize, &'static [u8])];
+
+/// Magic byte signatures checked by [sniff_mime], as `(signature, mime)`
+const MAGIC_BYTES: &[(Signature, &str)] = &[
+    (&[(0, b"\x89PNG\r\n\x1a\n")], "image/png"),
+    (&[(0, b"\xff\xd8\xff")], "image/jpeg"),
+    (&[(0, b"GIF87a")], "image/gif"),
+    (&[(0, b"GIF89a")], "image/gif"),
+    // RIFF containers carry their format after the 4 byte chunk size
+    (&[(0, b"RIFF"), (8, b"WEBP")], "image/webp"),
+    (&[(0, b"%PDF-")], "application/pdf"),
+    (&[(0, b"PK\x03\x04")], "application/zip"),
+    (&[(0, b"\x1f\x8b")], "application/gzip"),
+    (&[(0, b"\x28\xb5\x2f\xfd")], "application/zstd"),
+    (&[(0, b"\0asm")], "application/wasm"),
+    (&[(0, b"OggS")], "audio/ogg"),
+    (&[(0, b"ID3")], "audio/mpeg"),
+    (&[(0, b"RIFF"), (8, b"WAVE")], "audio/wav"),
+];
+
+/// Subtypes that are zip archives underneath, e.g. `application/epub+zip`
+/// or the OpenXML document formats
+fn is_zip_container(mime: &MimeType) -> bool {
+    mime.subtype.ends_with("+zip")
+        || mime.subtype.starts_with("vnd.openxmlformats")
+        || mime.subtype.starts_with("vnd.oasis.opendocument")
+        || mime.subtype == "java-archive"
+}
+
+/// Detects the media type of `bytes` from well known magic numbers
+pub fn sniff_mime(bytes: &[u8]) -> Option<MimeType> {
+    MAGIC_BYTES
+        .iter()
+        .find(|(signature, _)| {
+            signature.iter().all(|(offset, magic)| {
+                bytes
+                    .get(*offset..offset + magic.len())
+                    .is_some_and(|window| window == *magic)
+            })
+        })
+        .and_then(|(_, mime)| mime.parse().ok())
+}
+
+impl UserData {
+    /// Validates `mime` and stores it in its canonical form
+    pub fn new(mime: &str, data: Vec<u8>) -> PoseidonResult<Self> {
+        let mime: MimeType = mime.parse()?;
+
+        Ok(UserData {
+            mime: mime.to_string(),
+            data,
+        })
+    }
+
+    pub fn mime_type(&self) -> PoseidonResult<MimeType> {
+        self.mime.parse()
+    }
+
+    /// Compares the declared mime type with the one detected from magic
+    /// bytes. Content that has no recognizable signature is accepted.
+    pub fn check_mime_matches_content(&self) -> PoseidonResult<()> {
+        let declared = self.mime_type()?;
+
+        match sniff_mime(&self.data) {
+            Some(detected) if detected.essence() != declared.essence() => {
+                if detected.subtype == "zip" && is_zip_container(&declared) {
+                    return Ok(());
+                }
+
+                Err(PoseidonError::MimeTypeMismatch {
+                    declared: declared.essence(),
+                    detected: detected.essence(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
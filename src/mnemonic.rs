@@ -0,0 +1,191 @@
+use crate::{Ed25519Keypair, PoseidonError, PoseidonResult};
+use core::{fmt, str::FromStr};
+use hmac::{Hmac, Mac};
+use rand_core::OsRng;
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+const ED25519_CURVE_SEED: &[u8] = b"ed25519 seed";
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+const SOLANA_COIN_TYPE: u32 = 501;
+
+/// A BIP39 mnemonic using the English wordlist
+pub struct Mnemonic(bip39::Mnemonic);
+
+impl Mnemonic {
+    /// Generates a new mnemonic of 12, 15, 18, 21 or 24 words from the
+    /// operating system's random number generator
+    pub fn generate(word_count: usize) -> PoseidonResult<Self> {
+        bip39::Mnemonic::generate_in_with(&mut OsRng, bip39::Language::English, word_count)
+            .map(Mnemonic)
+            .map_err(|error| error.into())
+    }
+
+    /// Parses and validates a mnemonic. Whitespace is normalized and the
+    /// checksum is verified.
+    pub fn parse(phrase: &str) -> PoseidonResult<Self> {
+        bip39::Mnemonic::parse_in(bip39::Language::English, phrase)
+            .map(Mnemonic)
+            .map_err(|error| error.into())
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.0.word_count()
+    }
+
+    pub fn phrase(&self) -> Zeroizing<String> {
+        Zeroizing::new(self.0.to_string())
+    }
+
+    /// The 64 byte BIP39 seed for the mnemonic and optional passphrase
+    pub fn to_seed(&self, passphrase: &str) -> Zeroizing<[u8; 64]> {
+        Zeroizing::new(self.0.to_seed(passphrase))
+    }
+
+    /// Derives the keypair at `m/44'/501'/{account}'/0'`, the path used by the
+    /// Solana CLI and most Solana wallets.
+    pub fn to_keypair(&self, passphrase: &str, account: u32) -> PoseidonResult<Ed25519Keypair> {
+        Ok(self.to_keypair_with_path(passphrase, &DerivationPath::solana(account)?))
+    }
+
+    pub fn to_keypair_with_path(&self, passphrase: &str, path: &DerivationPath) -> Ed25519Keypair {
+        derive_ed25519_keypair(self.to_seed(passphrase).as_ref(), path)
+    }
+}
+
+impl FromStr for Mnemonic {
+    type Err = PoseidonError;
+
+    fn from_str(phrase: &str) -> Result<Self, Self::Err> {
+        Mnemonic::parse(phrase)
+    }
+}
+
+/// Only the word count is printed so the phrase never ends up in logs
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mnemonic")
+            .field("word_count", &self.word_count())
+            .finish()
+    }
+}
+
+impl From<bip39::Error> for PoseidonError {
+    fn from(error: bip39::Error) -> Self {
+        match error {
+            bip39::Error::BadWordCount(count) => PoseidonError::MnemonicInvalidWordCount(count),
+            bip39::Error::UnknownWord(index) => PoseidonError::MnemonicUnknownWord { index },
+            bip39::Error::InvalidChecksum => PoseidonError::MnemonicInvalidChecksum,
+            bip39::Error::BadEntropyBitCount(_) | bip39::Error::AmbiguousLanguages(_) => {
+                PoseidonError::Unspecified(format!("bip39::Error - `{}`", error))
+            }
+        }
+    }
+}
+
+/// A SLIP-0010 derivation path. Ed25519 only supports hardened derivation so
+/// every index is hardened.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Builds a path from unhardened index values, e.g. `[44, 501, 0, 0]`
+    pub fn new(indexes: &[u32]) -> PoseidonResult<Self> {
+        if let Some(index) = indexes.iter().find(|index| **index >= HARDENED_OFFSET) {
+            return Err(PoseidonError::InvalidDerivationPath(format!(
+                "index `{}` is out of range",
+                index
+            )));
+        }
+
+        Ok(DerivationPath(indexes.to_vec()))
+    }
+
+    /// `m/44'/501'/{account}'/0'`
+    pub fn solana(account: u32) -> PoseidonResult<Self> {
+        DerivationPath::new(&[44, SOLANA_COIN_TYPE, account, 0])
+    }
+
+    /// The unhardened index values of the path
+    pub fn indexes(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = PoseidonError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            |reason: &str| PoseidonError::InvalidDerivationPath(format!("`{}` {}", path, reason));
+
+        let mut segments = path.trim().split('/');
+        if segments.next() != Some("m") {
+            return Err(invalid("must start with `m`"));
+        }
+
+        let indexes = segments
+            .map(|segment| {
+                let index = segment
+                    .strip_suffix('\'')
+                    .or_else(|| segment.strip_suffix('h'))
+                    .or_else(|| segment.strip_suffix('H'))
+                    .ok_or_else(|| invalid("contains a non-hardened index"))?;
+
+                if index.is_empty() || !index.bytes().all(|byte| byte.is_ascii_digit()) {
+                    return Err(invalid("contains an index that is not a number"));
+                }
+
+                index
+                    .parse::<u32>()
+                    .map_err(|_| invalid("contains an index that is out of range"))
+            })
+            .collect::<PoseidonResult<Vec<u32>>>()?;
+
+        DerivationPath::new(&indexes)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        for index in &self.0 {
+            write!(f, "/{}'", index)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Derives an Ed25519 keypair from a BIP39 seed following SLIP-0010
+pub fn derive_ed25519_keypair(seed: &[u8], path: &DerivationPath) -> Ed25519Keypair {
+    let (mut key, mut chain_code) = hmac_sha512_split(ED25519_CURVE_SEED, &[seed]);
+
+    for index in path.indexes() {
+        let hardened_index = (index | HARDENED_OFFSET).to_be_bytes();
+        let (child_key, child_chain_code) = hmac_sha512_split(
+            chain_code.as_ref(),
+            &[&[0u8], key.as_ref(), &hardened_index],
+        );
+
+        key = child_key;
+        chain_code = child_chain_code;
+    }
+
+    Ed25519Keypair::from_seed(&key)
+}
+
+type KeyAndChainCode = (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>);
+
+fn hmac_sha512_split(key: &[u8], data: &[&[u8]]) -> KeyAndChainCode {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    data.iter().for_each(|chunk| mac.update(chunk));
+    let output = Zeroizing::new(<[u8; 64]>::from(mac.finalize().into_bytes()));
+
+    let mut left = Zeroizing::new([0u8; 32]);
+    let mut right = Zeroizing::new([0u8; 32]);
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+
+    (left, right)
+}
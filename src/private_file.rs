@@ -0,0 +1,37 @@
+use crate::PoseidonResult;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+/// Writes `bytes` to `path`, creating missing parent directories.
+/// On unix the file is only readable and writable by its owner (`0600`).
+pub(crate) fn write_private_file(path: &Path, bytes: &[u8]) -> PoseidonResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+
+    // `mode` only applies to newly created files, so tighten existing ones too
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+
+    file.write_all(bytes)?;
+    file.sync_all()?;
+
+    Ok(())
+}
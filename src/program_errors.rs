@@ -0,0 +1,248 @@
+use crate::{Ed25519PublicKey, InstructionError, TransactionError};
+use core::fmt;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const SPL_TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const SPL_ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// `TokenError` from `spl-token`. Token-2022 shares these codes but keeps
+/// adding its own after them, so it is not registered by default.
+#[rustfmt::skip]
+const SPL_TOKEN_ERRORS: &[(u32, &str, &str)] = &[
+    (0, "NotRentExempt", "Lamport balance below rent-exempt threshold"),
+    (1, "InsufficientFunds", "Insufficient funds"),
+    (2, "InvalidMint", "Invalid Mint"),
+    (3, "MintMismatch", "Account not associated with this Mint"),
+    (4, "OwnerMismatch", "Owner does not match"),
+    (5, "FixedSupply", "Fixed supply"),
+    (6, "AlreadyInUse", "Already in use"),
+    (7, "InvalidNumberOfProvidedSigners", "Invalid number of provided signers"),
+    (8, "InvalidNumberOfRequiredSigners", "Invalid number of required signers"),
+    (9, "UninitializedState", "State is uninitialized"),
+    (10, "NativeNotSupported", "Instruction does not support native tokens"),
+    (11, "NonNativeHasBalance", "Non-native account can only be closed if its balance is zero"),
+    (12, "InvalidInstruction", "Invalid instruction"),
+    (13, "InvalidState", "State is invalid for requested operation"),
+    (14, "Overflow", "Operation overflowed"),
+    (15, "AuthorityTypeNotSupported", "Account does not support specified authority type"),
+    (16, "MintCannotFreeze", "This token mint cannot freeze accounts"),
+    (17, "AccountFrozen", "Account is frozen"),
+    (18, "MintDecimalsMismatch", "The provided decimals value different from the Mint decimals"),
+    (19, "NonNativeNotSupported", "Instruction does not support non-native tokens"),
+];
+
+/// `AssociatedTokenAccountError` from `spl-associated-token-account`
+#[rustfmt::skip]
+const SPL_ASSOCIATED_TOKEN_ERRORS: &[(u32, &str, &str)] = &[(
+    0,
+    "InvalidOwner",
+    "Associated token account owner does not match address derivation",
+)];
+
+/// `ErrorCode` from `anchor-lang`, returned by every Anchor program.
+/// Program specific errors start at 6000.
+#[rustfmt::skip]
+const ANCHOR_ERRORS: &[(u32, &str, &str)] = &[
+    (100, "InstructionMissing", "8 byte instruction identifier not provided"),
+    (101, "InstructionFallbackNotFound", "Fallback functions are not supported"),
+    (102, "InstructionDidNotDeserialize", "The program could not deserialize the given instruction"),
+    (103, "InstructionDidNotSerialize", "The program could not serialize the given instruction"),
+    (1000, "IdlInstructionStub", "The program was compiled without idl instructions"),
+    (1001, "IdlInstructionInvalidProgram", "Invalid program given to the IDL instruction"),
+    (1002, "IdlAccountNotEmpty", "IDL account must be empty in order to resize, try closing first"),
+    (1500, "EventInstructionStub", "The program was compiled without `event-cpi` feature"),
+    (2000, "ConstraintMut", "A mut constraint was violated"),
+    (2001, "ConstraintHasOne", "A has one constraint was violated"),
+    (2002, "ConstraintSigner", "A signer constraint was violated"),
+    (2003, "ConstraintRaw", "A raw constraint was violated"),
+    (2004, "ConstraintOwner", "An owner constraint was violated"),
+    (2005, "ConstraintRentExempt", "A rent exemption constraint was violated"),
+    (2006, "ConstraintSeeds", "A seeds constraint was violated"),
+    (2007, "ConstraintExecutable", "An executable constraint was violated"),
+    (2008, "ConstraintState", "Deprecated Error, feel free to replace with something else"),
+    (2009, "ConstraintAssociated", "An associated constraint was violated"),
+    (2010, "ConstraintAssociatedInit", "An associated init constraint was violated"),
+    (2011, "ConstraintClose", "A close constraint was violated"),
+    (2012, "ConstraintAddress", "An address constraint was violated"),
+    (2013, "ConstraintZero", "Expected zero account discriminant"),
+    (2014, "ConstraintTokenMint", "A token mint constraint was violated"),
+    (2015, "ConstraintTokenOwner", "A token owner constraint was violated"),
+    (2016, "ConstraintMintMintAuthority", "A mint mint authority constraint was violated"),
+    (2017, "ConstraintMintFreezeAuthority", "A mint freeze authority constraint was violated"),
+    (2018, "ConstraintMintDecimals", "A mint decimals constraint was violated"),
+    (2019, "ConstraintSpace", "A space constraint was violated"),
+    (2020, "ConstraintAccountIsNone", "A required account for the constraint is None"),
+    (2021, "ConstraintTokenTokenProgram", "A token account token program constraint was violated"),
+    (2022, "ConstraintMintTokenProgram", "A mint token program constraint was violated"),
+    (2023, "ConstraintAssociatedTokenTokenProgram", "An associated token account token program constraint was violated"),
+    (2500, "RequireViolated", "A require expression was violated"),
+    (2501, "RequireEqViolated", "A require_eq expression was violated"),
+    (2502, "RequireKeysEqViolated", "A require_keys_eq expression was violated"),
+    (2503, "RequireNeqViolated", "A require_neq expression was violated"),
+    (2504, "RequireKeysNeqViolated", "A require_keys_neq expression was violated"),
+    (2505, "RequireGtViolated", "A require_gt expression was violated"),
+    (2506, "RequireGteViolated", "A require_gte expression was violated"),
+    (3000, "AccountDiscriminatorAlreadySet", "The account discriminator was already set on this account"),
+    (3001, "AccountDiscriminatorNotFound", "No 8 byte discriminator was found on the account"),
+    (3002, "AccountDiscriminatorMismatch", "8 byte discriminator did not match what was expected"),
+    (3003, "AccountDidNotDeserialize", "Failed to deserialize the account"),
+    (3004, "AccountDidNotSerialize", "Failed to serialize the account"),
+    (3005, "AccountNotEnoughKeys", "Not enough account keys given to the instruction"),
+    (3006, "AccountNotMutable", "The given account is not mutable"),
+    (3007, "AccountOwnedByWrongProgram", "The given account is owned by a different program than expected"),
+    (3008, "InvalidProgramId", "Program ID was not as expected"),
+    (3009, "InvalidProgramExecutable", "Program account is not executable"),
+    (3010, "AccountNotSigner", "The given account did not sign"),
+    (3011, "AccountNotSystemOwned", "The given account is not owned by the system program"),
+    (3012, "AccountNotInitialized", "The program expected this account to be already initialized"),
+    (3013, "AccountNotProgramData", "The given account is not a program data account"),
+    (3014, "AccountNotAssociatedTokenAccount", "The given account is not the associated token account"),
+    (3015, "AccountSysvarMismatch", "The given public key does not match the required sysvar"),
+    (3016, "AccountReallocExceedsLimit", "The account reallocation exceeds the MAX_PERMITTED_DATA_INCREASE limit"),
+    (3017, "AccountDuplicateReallocs", "The account was duplicated for more than one reallocation"),
+    (4100, "DeclaredProgramIdMismatch", "The declared program id does not match the actual program id"),
+    (4101, "TryingToInitPayerAsProgramAccount", "You cannot/should not initialize the payer account as a program account"),
+    (4102, "InvalidNumericConversion", "Program failed to convert numeric types"),
+    (5000, "Deprecated", "The API being used is deprecated and should no longer be used"),
+];
+
+/// The name and message of a custom program error
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProgramErrorInfo {
+    pub name: String,
+    pub message: String,
+}
+
+impl ProgramErrorInfo {
+    pub fn new(name: &str, message: &str) -> Self {
+        ProgramErrorInfo {
+            name: name.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+}
+
+/// Maps the `InstructionError::Custom` codes of known programs to names and
+/// messages. Names are the bare variant names, as in an Anchor IDL, without
+/// the enum they belong to.
+///
+/// Programs registered with [ProgramErrorRegistry::register_anchor_program]
+/// fall back to the Anchor framework errors for codes they do not define.
+#[derive(Debug, Clone, Default)]
+pub struct ProgramErrorRegistry {
+    programs: HashMap<Ed25519PublicKey, BTreeMap<u32, ProgramErrorInfo>>,
+    anchor_programs: HashSet<Ed25519PublicKey>,
+}
+
+impl ProgramErrorRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        ProgramErrorRegistry::default()
+    }
+
+    /// A registry with the SPL Token and Associated Token program errors
+    pub fn with_builtin() -> Self {
+        let mut registry = ProgramErrorRegistry::new();
+
+        for (program_id, errors) in [
+            (SPL_TOKEN_PROGRAM_ID, SPL_TOKEN_ERRORS),
+            (SPL_ASSOCIATED_TOKEN_PROGRAM_ID, SPL_ASSOCIATED_TOKEN_ERRORS),
+        ] {
+            let program_id = builtin_program_id(program_id);
+            for (code, name, message) in errors {
+                registry.register(program_id, *code, ProgramErrorInfo::new(name, message));
+            }
+        }
+
+        registry
+    }
+
+    pub fn register(&mut self, program_id: Ed25519PublicKey, code: u32, error: ProgramErrorInfo) {
+        self.programs
+            .entry(program_id)
+            .or_default()
+            .insert(code, error);
+    }
+
+    /// Registers every error of a program, e.g. from its IDL
+    pub fn register_all<I>(&mut self, program_id: Ed25519PublicKey, errors: I)
+    where
+        I: IntoIterator<Item = (u32, ProgramErrorInfo)>,
+    {
+        self.programs.entry(program_id).or_default().extend(errors);
+    }
+
+    /// Marks the program as an Anchor program so the framework error codes
+    /// apply to it
+    pub fn register_anchor_program(&mut self, program_id: Ed25519PublicKey) {
+        self.anchor_programs.insert(program_id);
+    }
+
+    /// Returns `None` for programs or codes that are not registered
+    pub fn lookup(&self, program_id: &Ed25519PublicKey, code: u32) -> Option<ProgramErrorInfo> {
+        if let Some(error) = self
+            .programs
+            .get(program_id)
+            .and_then(|errors| errors.get(&code))
+        {
+            return Some(error.clone());
+        }
+
+        if self.anchor_programs.contains(program_id) {
+            return ANCHOR_ERRORS
+                .iter()
+                .find(|(anchor_code, _, _)| *anchor_code == code)
+                .map(|(_, name, message)| ProgramErrorInfo::new(name, message));
+        }
+
+        None
+    }
+
+    /// Resolves the custom error of a failed transaction. `program_ids` holds
+    /// the program ID of every instruction of the transaction, in order.
+    pub fn describe(
+        &self,
+        error: &TransactionError,
+        program_ids: &[Ed25519PublicKey],
+    ) -> Option<ProgramErrorDescription> {
+        match error {
+            TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+                let program_id = *program_ids.get(usize::from(*index))?;
+
+                Some(ProgramErrorDescription {
+                    instruction_index: *index,
+                    program_id,
+                    code: *code,
+                    error: self.lookup(&program_id, *code)?,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A custom program error resolved by [ProgramErrorRegistry::describe]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramErrorDescription {
+    pub instruction_index: u8,
+    pub program_id: Ed25519PublicKey,
+    pub code: u32,
+    pub error: ProgramErrorInfo,
+}
+
+impl fmt::Display for ProgramErrorDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Error processing Instruction {}: {} ({:#x}): {}",
+            self.instruction_index, self.error.name, self.code, self.error.message
+        )
+    }
+}
+
+fn builtin_program_id(program_id: &str) -> Ed25519PublicKey {
+    program_id
+        .parse()
+        .expect("builtin program IDs are valid base58 public keys")
+}
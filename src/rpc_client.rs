@@ -0,0 +1,317 @@
+use crate::{
+    json_rpc, Base58BlockHash, Cluster, Commitment, Ed25519PublicKey, Ed25519Signature, Lamports,
+    PoseidonError, PoseidonResult, TransactionError, UnixTimestamp,
+};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use core::time::Duration;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::{json, Value};
+
+/// The timeout of every request unless [RpcClient::with_timeout] is used
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A blocking Solana JSON-RPC client. Errors returned by the node become
+/// [PoseidonError::Json], or [PoseidonError::Tx] when they carry a
+/// transaction error, e.g. a failed preflight simulation.
+#[derive(Debug, Clone)]
+pub struct RpcClient {
+    cluster: Cluster,
+    commitment: Commitment,
+    timeout: Duration,
+}
+
+/// An account as returned by `getAccountInfo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountInfo {
+    pub lamports: Lamports,
+    pub owner: Ed25519PublicKey,
+    pub data: Vec<u8>,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatestBlockhash {
+    pub blockhash: Base58BlockHash,
+    /// The last block height at which a transaction using the blockhash is
+    /// accepted
+    pub last_valid_block_height: u64,
+}
+
+/// The status of a transaction as returned by `getSignatureStatuses`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureStatus {
+    pub slot: u64,
+    /// `None` once the block is rooted
+    pub confirmations: Option<u64>,
+    /// Errors this crate does not know are kept as
+    /// [TransactionError::Unknown]
+    #[serde(default, deserialize_with = "deserialize_transaction_error")]
+    pub err: Option<TransactionError>,
+    pub confirmation_status: Option<Commitment>,
+}
+
+impl SignatureStatus {
+    /// Whether the transaction reached `commitment`, successfully or not
+    pub fn satisfies(&self, commitment: Commitment) -> bool {
+        matches!(self.confirmation_status, Some(status) if status >= commitment)
+    }
+}
+
+/// A confirmed transaction as returned by `getTransaction`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionInfo {
+    pub slot: u64,
+    pub block_time: Option<UnixTimestamp>,
+    /// The wire encoding of the signed transaction
+    pub transaction: Vec<u8>,
+    /// Errors this crate does not know are kept as
+    /// [TransactionError::Unknown]
+    pub err: Option<TransactionError>,
+    pub fee: Lamports,
+    pub log_messages: Vec<String>,
+}
+
+/// The `{ context, value }` wrapper of most responses
+#[derive(Deserialize)]
+struct WithContext<T> {
+    value: T,
+}
+
+/// Binary data as a `[data, encoding]` pair
+#[derive(Deserialize)]
+struct EncodedBytes(String, String);
+
+impl EncodedBytes {
+    fn decode(self) -> PoseidonResult<Vec<u8>> {
+        if self.1 != "base64" {
+            return Err(PoseidonError::UnsupportedEncoding(format!(
+                "the node answered with `{}` instead of `base64`",
+                self.1
+            )));
+        }
+
+        BASE64_STANDARD
+            .decode(self.0)
+            .map_err(|error| PoseidonError::InvalidEncodedData(error.to_string()))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawAccountInfo {
+    lamports: Lamports,
+    owner: Ed25519PublicKey,
+    data: EncodedBytes,
+    executable: bool,
+    rent_epoch: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTransactionInfo {
+    slot: u64,
+    block_time: Option<UnixTimestamp>,
+    transaction: EncodedBytes,
+    meta: Option<RawTransactionMeta>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTransactionMeta {
+    #[serde(default, deserialize_with = "deserialize_transaction_error")]
+    err: Option<TransactionError>,
+    fee: Lamports,
+    log_messages: Option<Vec<String>>,
+}
+
+/// Newer nodes report transaction errors this crate does not know yet. The
+/// transaction still failed, so such an error is kept as its JSON instead of
+/// failing the whole response.
+fn deserialize_transaction_error<'de, D>(
+    deserializer: D,
+) -> Result<Option<TransactionError>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Value>::deserialize(deserializer)?.map(|value| {
+        TransactionError::deserialize(&value)
+            .unwrap_or_else(|_| TransactionError::Unknown(value.to_string()))
+    }))
+}
+
+impl RpcClient {
+    pub fn new(cluster: Cluster) -> Self {
+        RpcClient {
+            cluster,
+            commitment: Commitment::default(),
+            timeout: DEFAULT_RPC_TIMEOUT,
+        }
+    }
+
+    pub fn with_commitment(mut self, commitment: Commitment) -> Self {
+        self.commitment = commitment;
+
+        self
+    }
+
+    /// minreq only supports whole seconds, shorter timeouts are rounded up
+    /// to one second
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    pub fn cluster(&self) -> &Cluster {
+        &self.cluster
+    }
+
+    pub fn commitment(&self) -> Commitment {
+        self.commitment
+    }
+
+    /// Calls any JSON-RPC method, for the ones without a typed wrapper
+    pub fn call<R: DeserializeOwned>(&self, method: &str, params: Value) -> PoseidonResult<R> {
+        json_rpc::call(self.cluster.url(), method, params, self.timeout)
+    }
+
+    /// `Ok` when the node is healthy, the node error otherwise
+    pub fn get_health(&self) -> PoseidonResult<()> {
+        let health: String = self.call("getHealth", Value::Null)?;
+
+        match health.as_str() {
+            "ok" => Ok(()),
+            _ => Err(PoseidonError::NodeUnhealthy(format!(
+                "the node reports `{}`",
+                health
+            ))),
+        }
+    }
+
+    pub fn get_slot(&self) -> PoseidonResult<u64> {
+        self.call("getSlot", json!([self.config()]))
+    }
+
+    /// `None` if the account does not exist
+    pub fn get_account_info(
+        &self,
+        public_key: &Ed25519PublicKey,
+    ) -> PoseidonResult<Option<AccountInfo>> {
+        let response: WithContext<Option<RawAccountInfo>> = self.call(
+            "getAccountInfo",
+            json!([
+                public_key.to_base58(),
+                {
+                    "encoding": "base64",
+                    "commitment": self.commitment,
+                }
+            ]),
+        )?;
+
+        response
+            .value
+            .map(|account| {
+                Ok(AccountInfo {
+                    lamports: account.lamports,
+                    owner: account.owner,
+                    data: account.data.decode()?,
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                })
+            })
+            .transpose()
+    }
+
+    pub fn get_balance(&self, public_key: &Ed25519PublicKey) -> PoseidonResult<Lamports> {
+        let response: WithContext<Lamports> =
+            self.call("getBalance", json!([public_key.to_base58(), self.config()]))?;
+
+        Ok(response.value)
+    }
+
+    pub fn get_latest_blockhash(&self) -> PoseidonResult<LatestBlockhash> {
+        let response: WithContext<LatestBlockhash> =
+            self.call("getLatestBlockhash", json!([self.config()]))?;
+
+        Ok(response.value)
+    }
+
+    /// Submits a signed transaction in its wire encoding. The node simulates
+    /// it first at the commitment of the client, a failed simulation is
+    /// returned as [PoseidonError::Tx].
+    pub fn send_transaction(&self, transaction: &[u8]) -> PoseidonResult<Ed25519Signature> {
+        self.call(
+            "sendTransaction",
+            json!([
+                BASE64_STANDARD.encode(transaction),
+                {
+                    "encoding": "base64",
+                    "preflightCommitment": self.commitment,
+                }
+            ]),
+        )
+    }
+
+    /// The statuses in the order of `signatures`, `None` for transactions
+    /// the node does not know about
+    pub fn get_signature_statuses(
+        &self,
+        signatures: &[Ed25519Signature],
+    ) -> PoseidonResult<Vec<Option<SignatureStatus>>> {
+        let signatures: Vec<_> = signatures
+            .iter()
+            .map(|signature| signature.to_base58())
+            .collect();
+        let response: WithContext<Vec<Option<SignatureStatus>>> = self.call(
+            "getSignatureStatuses",
+            json!([signatures, { "searchTransactionHistory": true }]),
+        )?;
+
+        Ok(response.value)
+    }
+
+    /// `None` if the transaction is not confirmed yet or unknown to the node
+    pub fn get_transaction(
+        &self,
+        signature: &Ed25519Signature,
+    ) -> PoseidonResult<Option<TransactionInfo>> {
+        // Nodes only serve transactions from confirmed blocks
+        let commitment = self.commitment.max(Commitment::Confirmed);
+        let response: Option<RawTransactionInfo> = self.call(
+            "getTransaction",
+            json!([
+                signature.to_base58(),
+                {
+                    "encoding": "base64",
+                    "commitment": commitment,
+                    "maxSupportedTransactionVersion": 0,
+                }
+            ]),
+        )?;
+
+        response
+            .map(|transaction| {
+                let (err, fee, log_messages) = match transaction.meta {
+                    Some(meta) => (meta.err, meta.fee, meta.log_messages.unwrap_or_default()),
+                    None => (None, 0, Vec::new()),
+                };
+
+                Ok(TransactionInfo {
+                    slot: transaction.slot,
+                    block_time: transaction.block_time,
+                    transaction: transaction.transaction.decode()?,
+                    err,
+                    fee,
+                    log_messages,
+                })
+            })
+            .transpose()
+    }
+
+    fn config(&self) -> Value {
+        json!({ "commitment": self.commitment })
+    }
+}
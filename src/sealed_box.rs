@@ -0,0 +1,153 @@
+//! Anonymous public key encryption to an [Ed25519PublicKey].
+//!
+//! The recipient's Ed25519 keys are converted to their X25519 equivalents, a
+//! fresh ephemeral X25519 key performs Diffie-Hellman with the recipient and
+//! the shared secret is expanded with HKDF-SHA256 into an XChaCha20-Poly1305
+//! key.
+//!
+//! Wire layout of [EncryptedData], version `1`:
+//!
+//! | Offset | Size     | Field                                              |
+//! |--------|----------|----------------------------------------------------|
+//! | 0      | 1        | version, `1`                                       |
+//! | 1      | 32       | ephemeral X25519 public key                        |
+//! | 33     | 24       | XChaCha20-Poly1305 nonce                           |
+//! | 57     | n + 16   | ciphertext followed by the Poly1305 tag            |
+//!
+//! The HKDF salt is `ephemeral public key || recipient X25519 public key`, the
+//! info string is `poseidon-sealed-box-v1` and the version byte together with
+//! the ephemeral public key is authenticated as associated data.
+
+use crate::{
+    Ed25519Keypair, Ed25519PublicKey, EncryptedData, PoseidonError, PoseidonResult, UserData,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use curve25519_dalek::MontgomeryPoint;
+use hkdf::Hkdf;
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+pub const SEALED_BOX_VERSION: u8 = 1;
+const HKDF_INFO: &[u8] = b"poseidon-sealed-box-v1";
+const HEADER_LENGTH: usize = 1 + 32;
+const NONCE_LENGTH: usize = 24;
+const TAG_LENGTH: usize = 16;
+
+/// Encrypts `plaintext` so that only the holder of the keypair for
+/// `recipient` can decrypt it
+pub fn seal_bytes(recipient: &Ed25519PublicKey, plaintext: &[u8]) -> PoseidonResult<EncryptedData> {
+    let recipient = recipient.to_x25519()?;
+
+    let mut ephemeral_secret = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(ephemeral_secret.as_mut());
+    let ephemeral_public = MontgomeryPoint::mul_base_clamped(*ephemeral_secret).to_bytes();
+    let shared_secret = Zeroizing::new(
+        MontgomeryPoint(recipient)
+            .mul_clamped(*ephemeral_secret)
+            .to_bytes(),
+    );
+    if is_low_order(&shared_secret) {
+        return Err(PoseidonError::InvalidEd25519PublicKey);
+    }
+
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut sealed =
+        Vec::with_capacity(HEADER_LENGTH + NONCE_LENGTH + plaintext.len() + TAG_LENGTH);
+    sealed.push(SEALED_BOX_VERSION);
+    sealed.extend_from_slice(&ephemeral_public);
+
+    let ciphertext = cipher(&shared_secret, &ephemeral_public, &recipient)
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &sealed,
+            },
+        )
+        .map_err(|_| PoseidonError::Unspecified("sealed box encryption failed".to_owned()))?;
+
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+
+    Ok(sealed)
+}
+
+/// Decrypts data produced by [seal_bytes] for the public key of `keypair`
+pub fn open_bytes(keypair: &Ed25519Keypair, sealed: &[u8]) -> PoseidonResult<Vec<u8>> {
+    if sealed.len() < HEADER_LENGTH + NONCE_LENGTH + TAG_LENGTH {
+        return Err(PoseidonError::InvalidEncryptedData);
+    }
+    if sealed[0] != SEALED_BOX_VERSION {
+        return Err(PoseidonError::InvalidEncryptedData);
+    }
+
+    let (header, body) = sealed.split_at(HEADER_LENGTH);
+    let (nonce, ciphertext) = body.split_at(NONCE_LENGTH);
+    let mut ephemeral_public = [0u8; 32];
+    ephemeral_public.copy_from_slice(&header[1..]);
+
+    let recipient = keypair.public_key().to_x25519()?;
+    let shared_secret = Zeroizing::new(
+        MontgomeryPoint(ephemeral_public)
+            .mul_clamped(*keypair.to_x25519_secret())
+            .to_bytes(),
+    );
+    if is_low_order(&shared_secret) {
+        return Err(PoseidonError::DecryptionFailed);
+    }
+
+    cipher(&shared_secret, &ephemeral_public, &recipient)
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| PoseidonError::DecryptionFailed)
+}
+
+impl UserData {
+    /// Borsh serializes the data and seals it for `recipient`
+    pub fn seal(&self, recipient: &Ed25519PublicKey) -> PoseidonResult<EncryptedData> {
+        let plaintext = Zeroizing::new(self.try_to_vec()?);
+
+        seal_bytes(recipient, &plaintext)
+    }
+
+    /// Opens data sealed with [UserData::seal]
+    pub fn open(keypair: &Ed25519Keypair, sealed: &[u8]) -> PoseidonResult<UserData> {
+        let plaintext = Zeroizing::new(open_bytes(keypair, sealed)?);
+
+        UserData::try_from_slice(&plaintext).map_err(|_| PoseidonError::InvalidEncryptedData)
+    }
+}
+
+/// Diffie-Hellman with a low order point yields an all zero shared secret
+fn is_low_order(shared_secret: &[u8; 32]) -> bool {
+    shared_secret.iter().all(|byte| *byte == 0)
+}
+
+fn cipher(
+    shared_secret: &[u8; 32],
+    ephemeral_public: &[u8; 32],
+    recipient: &[u8; 32],
+) -> XChaCha20Poly1305 {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_public);
+    salt[32..].copy_from_slice(recipient);
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret)
+        .expand(HKDF_INFO, key.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+
+    XChaCha20Poly1305::new_from_slice(key.as_ref()).expect("key is 32 bytes long")
+}
@@ -0,0 +1,183 @@
+use poseidon_common::{
+    ChunkAssembler, ChunkManifest, ChunkReader, PoseidonError, UserData, UserDataChunk,
+};
+
+fn payload(len: usize) -> UserData {
+    UserData {
+        mime: "application/octet-stream".to_owned(),
+        data: (0..len).map(|byte| byte as u8).collect(),
+    }
+}
+
+#[test]
+fn chunks_round_trip() {
+    for len in [0, 1, 15, 16, 17, 100] {
+        let data = payload(len);
+        let (manifest, chunks) = data.to_chunks(16).unwrap();
+
+        assert_eq!(manifest.total_len, len as u64);
+        assert_eq!(manifest.chunk_count().unwrap() as usize, len.div_ceil(16));
+        assert_eq!(chunks.len(), len.div_ceil(16));
+        for (index, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.index as usize, index);
+            assert_eq!(chunk.data.len() as u64, manifest.chunk_len(chunk.index));
+        }
+        assert_eq!(manifest.verify(), Ok(()));
+
+        let reassembled = UserData::from_chunks(manifest, &chunks).unwrap();
+        assert_eq!(reassembled.mime, data.mime);
+        assert_eq!(reassembled.data, data.data);
+    }
+}
+
+#[test]
+fn readers_stream_into_assemblers() {
+    let data = payload(100);
+    let mut reader = ChunkReader::new(&data.mime, data.data.as_slice(), 32).unwrap();
+    let chunks: Vec<UserDataChunk> = reader.by_ref().map(Result::unwrap).collect();
+    let manifest = reader.into_manifest().unwrap();
+
+    let mut assembler = ChunkAssembler::new(manifest, Vec::new()).unwrap();
+    for chunk in &chunks {
+        assembler.write_chunk(chunk).unwrap();
+    }
+    assert_eq!(assembler.finish().unwrap(), data.data);
+
+    // The manifest is only known once the reader is exhausted
+    let reader = ChunkReader::new(&data.mime, data.data.as_slice(), 32).unwrap();
+    assert_eq!(
+        reader.into_manifest(),
+        Err(PoseidonError::ChunkStreamIncomplete)
+    );
+    assert!(matches!(
+        ChunkReader::new(&data.mime, data.data.as_slice(), 0),
+        Err(PoseidonError::InvalidChunkManifest(_))
+    ));
+}
+
+#[test]
+fn the_root_is_not_the_data_id() {
+    let data = payload(100);
+    let (manifest, _) = data.to_chunks(16).unwrap();
+
+    assert_eq!(
+        manifest.root,
+        ChunkManifest::compute_root(
+            &manifest.mime,
+            manifest.total_len,
+            manifest.chunk_size,
+            &manifest.chunk_hashes
+        )
+        .unwrap()
+    );
+    assert_ne!(manifest.root, data.data_id().unwrap());
+
+    // The same payload in other chunk sizes has other roots
+    let (other, _) = data.to_chunks(32).unwrap();
+    assert_ne!(other.root, manifest.root);
+}
+
+#[test]
+fn chunks_out_of_order_are_rejected() {
+    let (manifest, chunks) = payload(100).to_chunks(16).unwrap();
+    let mut assembler = ChunkAssembler::new(manifest, Vec::new()).unwrap();
+
+    assert_eq!(
+        assembler.write_chunk(&chunks[1]),
+        Err(PoseidonError::ChunkOutOfOrder {
+            expected: 0,
+            found: 1
+        })
+    );
+    assembler.write_chunk(&chunks[0]).unwrap();
+    assert_eq!(
+        assembler.write_chunk(&chunks[0]),
+        Err(PoseidonError::ChunkOutOfOrder {
+            expected: 1,
+            found: 0
+        })
+    );
+
+    // An index past the last chunk
+    let (manifest, chunks) = payload(16).to_chunks(16).unwrap();
+    let mut assembler = ChunkAssembler::new(manifest, Vec::new()).unwrap();
+    assembler.write_chunk(&chunks[0]).unwrap();
+    let extra = UserDataChunk {
+        index: 1,
+        data: Vec::new(),
+    };
+    assert_eq!(
+        assembler.write_chunk(&extra),
+        Err(PoseidonError::ChunkOutOfOrder {
+            expected: 1,
+            found: 1
+        })
+    );
+}
+
+#[test]
+fn missing_chunks_are_detected() {
+    let (manifest, chunks) = payload(100).to_chunks(16).unwrap();
+
+    assert_eq!(
+        UserData::from_chunks(manifest.clone(), &chunks[..chunks.len() - 1]).map(|_| ()),
+        Err(PoseidonError::ChunkStreamIncomplete)
+    );
+    assert_eq!(
+        UserData::from_chunks(manifest, chunks.iter().skip(1)).map(|_| ()),
+        Err(PoseidonError::ChunkOutOfOrder {
+            expected: 0,
+            found: 1
+        })
+    );
+}
+
+#[test]
+fn corrupted_chunks_are_detected() {
+    let (manifest, chunks) = payload(100).to_chunks(16).unwrap();
+
+    let mut flipped = chunks.clone();
+    flipped[2].data[0] ^= 1;
+    assert_eq!(
+        UserData::from_chunks(manifest.clone(), &flipped).map(|_| ()),
+        Err(PoseidonError::ChunkIntegrityMismatch { index: 2 })
+    );
+
+    let mut truncated = chunks;
+    truncated[6].data.pop();
+    assert_eq!(
+        UserData::from_chunks(manifest, &truncated).map(|_| ()),
+        Err(PoseidonError::ChunkIntegrityMismatch { index: 6 })
+    );
+}
+
+#[test]
+fn tampered_manifests_are_rejected() {
+    let (manifest, _) = payload(100).to_chunks(16).unwrap();
+    let is_invalid = |manifest: ChunkManifest| {
+        matches!(
+            ChunkAssembler::new(manifest, Vec::new()).map(|_| ()),
+            Err(PoseidonError::InvalidChunkManifest(_))
+        )
+    };
+
+    let mut tampered = manifest.clone();
+    tampered.mime = "text/plain".to_owned();
+    assert!(is_invalid(tampered));
+
+    let mut tampered = manifest.clone();
+    tampered.chunk_hashes.swap(0, 1);
+    assert!(is_invalid(tampered));
+
+    let mut tampered = manifest.clone();
+    tampered.chunk_hashes.pop();
+    assert!(is_invalid(tampered));
+
+    let mut tampered = manifest.clone();
+    tampered.total_len += 16;
+    assert!(is_invalid(tampered));
+
+    let mut tampered = manifest;
+    tampered.chunk_size = 0;
+    assert!(is_invalid(tampered));
+}
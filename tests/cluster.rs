@@ -0,0 +1,168 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use poseidon_common::{Cluster, PoseidonError};
+
+#[test]
+fn monikers_and_urls() {
+    let cases = [
+        ("mainnet-beta", Cluster::MainnetBeta),
+        ("m", Cluster::MainnetBeta),
+        ("devnet", Cluster::Devnet),
+        ("t", Cluster::Testnet),
+        ("localhost", Cluster::Localnet),
+        ("http://127.0.0.1:8899", Cluster::Localnet),
+        ("http://localhost:8899/", Cluster::Localnet),
+        ("https://api.devnet.solana.com/", Cluster::Devnet),
+        (
+            "https://rpc.example.com/v1/key",
+            Cluster::Custom {
+                rpc_url: "https://rpc.example.com/v1/key".to_owned(),
+                ws_url: None,
+            },
+        ),
+    ];
+
+    for (value, cluster) in cases {
+        assert_eq!(value.parse::<Cluster>().unwrap(), cluster, "{}", value);
+        assert_eq!(cluster.to_string().parse::<Cluster>().unwrap(), cluster);
+    }
+}
+
+#[test]
+fn invalid_clusters_are_rejected() {
+    for value in [
+        "mainnet-alpha",
+        "ftp://rpc.example.com",
+        "https://",
+        "https://rpc.example.com:port",
+        "https://rpc example.com",
+    ] {
+        assert!(
+            matches!(
+                value.parse::<Cluster>(),
+                Err(PoseidonError::InvalidCluster(..))
+            ),
+            "{}",
+            value
+        );
+    }
+
+    assert!(Cluster::custom("https://[::1]:8899", Some("wss://[::1]:8900".to_owned())).is_ok());
+    assert!(Cluster::custom(
+        "https://rpc.example.com",
+        Some("https://rpc.example.com".to_owned())
+    )
+    .is_err());
+}
+
+#[test]
+fn existing_encodings_are_unchanged() {
+    for (cluster, index) in [
+        (Cluster::MainnetBeta, 0u8),
+        (Cluster::MainnetBetaSerum, 1),
+        (Cluster::Testnet, 2),
+        (Cluster::Devnet, 3),
+        (Cluster::Localnet, 4),
+    ] {
+        assert_eq!(cluster.try_to_vec().unwrap(), [index]);
+        assert_eq!(Cluster::try_from_slice(&[index]).unwrap(), cluster);
+    }
+
+    #[cfg(feature = "serde_json")]
+    {
+        assert_eq!(
+            serde_json::to_string(&Cluster::Devnet).unwrap(),
+            r#""Devnet""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Cluster>(r#""MainnetBeta""#).unwrap(),
+            Cluster::MainnetBeta
+        );
+    }
+}
+
+#[test]
+fn custom_clusters_are_validated_when_decoded() {
+    let valid = Cluster::custom(
+        "https://rpc.example.com",
+        Some("wss://ws.example.com".to_owned()),
+    )
+    .unwrap();
+    let invalid = [
+        Cluster::Custom {
+            rpc_url: "ftp://rpc.example.com".to_owned(),
+            ws_url: None,
+        },
+        Cluster::Custom {
+            rpc_url: "https://rpc.example.com".to_owned(),
+            ws_url: Some("https://ws.example.com".to_owned()),
+        },
+    ];
+
+    let encoded = valid.try_to_vec().unwrap();
+    assert_eq!(Cluster::try_from_slice(&encoded).unwrap(), valid);
+    for cluster in &invalid {
+        assert!(Cluster::try_from_slice(&cluster.try_to_vec().unwrap()).is_err());
+    }
+
+    #[cfg(feature = "serde_json")]
+    {
+        let json = serde_json::to_string(&valid).unwrap();
+        assert_eq!(
+            json,
+            r#"{"Custom":{"rpc_url":"https://rpc.example.com","ws_url":"wss://ws.example.com"}}"#
+        );
+        assert_eq!(serde_json::from_str::<Cluster>(&json).unwrap(), valid);
+
+        for cluster in &invalid {
+            let json = serde_json::to_string(cluster).unwrap();
+            let error = serde_json::from_str::<Cluster>(&json).unwrap_err();
+            assert!(
+                error.to_string().starts_with("invalid cluster: "),
+                "{}",
+                error
+            );
+        }
+    }
+}
+
+#[test]
+fn websocket_urls() {
+    let cases = [
+        (Cluster::MainnetBeta, "wss://api.mainnet-beta.solana.com"),
+        (Cluster::Devnet, "wss://api.devnet.solana.com"),
+        (Cluster::Localnet, "ws://127.0.0.1:8900"),
+        (
+            Cluster::custom("https://rpc.example.com/v1/key?a=1", None).unwrap(),
+            "wss://rpc.example.com/v1/key?a=1",
+        ),
+        (
+            Cluster::custom("http://user@[::1]:9000/", None).unwrap(),
+            "ws://user@[::1]:9001/",
+        ),
+        (
+            Cluster::custom(
+                "https://rpc.example.com",
+                Some("wss://ws.example.com".to_owned()),
+            )
+            .unwrap(),
+            "wss://ws.example.com",
+        ),
+    ];
+
+    for (cluster, ws_url) in cases {
+        assert_eq!(cluster.ws_url(), ws_url, "{:?}", cluster);
+    }
+}
+
+#[test]
+fn genesis_hashes() {
+    assert_eq!(
+        Cluster::MainnetBeta.genesis_hash(),
+        Some("5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d")
+    );
+    assert_eq!(
+        Cluster::Devnet.genesis_hash(),
+        Some("EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG")
+    );
+    assert_eq!(Cluster::Localnet.genesis_hash(), None);
+}
@@ -0,0 +1,221 @@
+//! Failover of `ClusterPool` against mock RPC nodes served on localhost
+
+#![cfg(feature = "http")]
+
+mod common;
+
+use common::{error, mock_node, result};
+use poseidon_common::{
+    Cluster, ClusterPool, EndpointHealth, JsonError, PoseidonError, PoseidonErrorKind,
+    TransactionError,
+};
+use serde_json::json;
+use std::net::TcpListener;
+
+fn healthy_node(slot: u64) -> Cluster {
+    mock_node(move |request| match request["method"].as_str().unwrap() {
+        "getHealth" => result(json!("ok")),
+        "getSlot" => result(json!(slot)),
+        _ => (404, String::new()),
+    })
+}
+
+fn unhealthy_node() -> Cluster {
+    mock_node(|_| error(json!({ "code": -32005, "message": "Node is unhealthy" })))
+}
+
+fn behind_node() -> Cluster {
+    mock_node(|_| result(json!("behind")))
+}
+
+fn rate_limited_node() -> Cluster {
+    mock_node(|_| (429, "Too many requests".to_owned()))
+}
+
+fn offline_node() -> Cluster {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    Cluster::custom(url, None).unwrap()
+}
+
+#[test]
+fn probing_finds_lagging_and_unhealthy_nodes() {
+    let leader = healthy_node(1_000);
+    let mut pool = ClusterPool::new()
+        .with_max_slot_lag(50)
+        .with_endpoint(leader.clone(), 1)
+        .with_endpoint(healthy_node(900), 10)
+        .with_endpoint(unhealthy_node(), 10)
+        .with_endpoint(behind_node(), 10)
+        .with_endpoint(rate_limited_node(), 10)
+        .with_endpoint(offline_node(), 10);
+
+    pool.probe();
+
+    let health: Vec<_> = pool
+        .endpoints()
+        .iter()
+        .map(|endpoint| endpoint.health().clone())
+        .collect();
+    assert_eq!(health[0], EndpointHealth::Healthy { slot: 1_000 });
+    assert_eq!(
+        health[1],
+        EndpointHealth::Lagging {
+            slot: 900,
+            behind: 100
+        }
+    );
+    for health in &health[2..] {
+        assert!(
+            matches!(health, EndpointHealth::Unhealthy { .. }),
+            "{:?}",
+            health
+        );
+    }
+    assert_eq!(
+        health[3],
+        EndpointHealth::Unhealthy {
+            reason: "unhealthy RPC node: the node reports `behind`".to_owned()
+        }
+    );
+
+    // Weights only apply among the healthiest endpoints
+    for _ in 0..5 {
+        assert_eq!(pool.pick().unwrap(), &leader);
+    }
+}
+
+#[test]
+fn requests_follow_the_weights() {
+    let heavy = Cluster::custom("http://heavy.example.com", None).unwrap();
+    let light = Cluster::custom("http://light.example.com", None).unwrap();
+    let mut pool = ClusterPool::new()
+        .with_endpoint(heavy.clone(), 3)
+        .with_endpoint(light.clone(), 1);
+
+    let picks: Vec<_> = (0..8).map(|_| pool.pick().unwrap().clone()).collect();
+    assert_eq!(picks.iter().filter(|cluster| **cluster == heavy).count(), 6);
+    assert_eq!(picks.iter().filter(|cluster| **cluster == light).count(), 2);
+    // Smooth round robin interleaves the light endpoint instead of sending
+    // it every fourth request in a burst
+    assert_eq!(picks[..4], [heavy.clone(), heavy.clone(), light, heavy]);
+}
+
+fn connection_refused() -> PoseidonError {
+    PoseidonError::IoErr(PoseidonErrorKind::ConnectionRefused.into())
+}
+
+fn node_unhealthy() -> PoseidonError {
+    PoseidonError::Json(JsonError {
+        code: -32005,
+        message: "Node is unhealthy".to_owned(),
+        data: None,
+    })
+}
+
+#[test]
+fn calls_fail_over_when_endpoints_fail() {
+    let primary = Cluster::custom("http://primary.example.com", None).unwrap();
+    let backup = Cluster::custom("http://backup.example.com", None).unwrap();
+    let mut pool = ClusterPool::new()
+        .with_endpoint(primary.clone(), 10)
+        .with_endpoint(backup.clone(), 1);
+
+    let outcome = pool.call(|cluster| {
+        if *cluster == primary {
+            Err(connection_refused())
+        } else {
+            Ok(cluster.url().to_owned())
+        }
+    });
+    assert_eq!(outcome.unwrap(), backup.url());
+    assert!(matches!(
+        pool.endpoints()[0].health(),
+        EndpointHealth::Unhealthy { .. }
+    ));
+
+    // Once every endpoint failed the last error is returned
+    let outcome: Result<(), _> = pool.call(|_| Err(node_unhealthy()));
+    assert_eq!(outcome, Err(node_unhealthy()));
+    assert_eq!(pool.pick(), Err(PoseidonError::NoHealthyEndpoint));
+
+    // Nodes that answer `getHealth` with anything but `ok`
+    let behind = || PoseidonError::NodeUnhealthy("the node reports `behind`".to_owned());
+    let mut pool = ClusterPool::new()
+        .with_endpoint(primary.clone(), 1)
+        .with_endpoint(backup.clone(), 1);
+    let outcome: Result<(), _> = pool.call(|_| Err(behind()));
+    assert_eq!(outcome, Err(behind()));
+    assert_eq!(pool.pick(), Err(PoseidonError::NoHealthyEndpoint));
+
+    // Rate limited and overloaded nodes
+    for status in [429, 503] {
+        let busy = || PoseidonError::from_http_status(status, "busy").unwrap();
+        let mut pool = ClusterPool::new()
+            .with_endpoint(primary.clone(), 1)
+            .with_endpoint(backup.clone(), 1);
+        let mut attempts = 0;
+        let outcome: Result<(), _> = pool.call(|_| {
+            attempts += 1;
+            Err(busy())
+        });
+
+        assert_eq!(outcome, Err(busy()));
+        assert_eq!(attempts, 2, "{}", status);
+    }
+}
+
+#[test]
+fn other_errors_are_returned_without_failing_over() {
+    let primary = Cluster::custom("http://primary.example.com", None).unwrap();
+    let backup = Cluster::custom("http://backup.example.com", None).unwrap();
+    let mut pool = ClusterPool::new()
+        .with_endpoint(primary, 1)
+        .with_endpoint(backup, 1);
+
+    // Transaction errors, retryable or not, are about the transaction and
+    // not the node, as are user and permanent errors
+    let errors: [fn() -> PoseidonError; 6] = [
+        || PoseidonError::Tx(TransactionError::BlockhashNotFound),
+        || PoseidonError::Tx(TransactionError::AccountInUse),
+        || PoseidonError::Tx(TransactionError::WouldExceedMaxBlockCostLimit),
+        || PoseidonError::Tx(TransactionError::ClusterMaintenance),
+        || PoseidonError::InvalidBase58Ed25519PublicKey,
+        || {
+            PoseidonError::Json(JsonError {
+                code: -32602,
+                message: "Invalid params".to_owned(),
+                data: None,
+            })
+        },
+    ];
+
+    for error in errors {
+        let mut attempts = 0;
+        let outcome: Result<(), _> = pool.call(|_| {
+            attempts += 1;
+            Err(error())
+        });
+
+        assert_eq!(outcome, Err(error()));
+        assert_eq!(attempts, 1, "{}", error());
+    }
+
+    for endpoint in pool.endpoints() {
+        assert_eq!(endpoint.health(), &EndpointHealth::Unknown);
+    }
+}
+
+#[test]
+fn probing_brings_endpoints_back() {
+    let node = healthy_node(42);
+    let mut pool = ClusterPool::new().with_endpoint(node.clone(), 1);
+
+    let _: Result<(), _> = pool.call(|_| Err(connection_refused()));
+    assert!(pool.pick().is_err());
+
+    pool.probe();
+    assert_eq!(pool.pick().unwrap(), &node);
+}
@@ -0,0 +1,72 @@
+//! A mock Solana RPC node served on localhost
+
+use poseidon_common::Cluster;
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread,
+};
+
+/// Serves JSON-RPC requests with `respond`, which maps a request to an HTTP
+/// status and body. Returns the cluster of the node.
+pub fn mock_node<F>(respond: F) -> Cluster
+where
+    F: Fn(&Value) -> (u16, String) + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+
+            let (status, body) = respond(&request);
+            write!(
+                stream,
+                "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+
+    Cluster::custom(url, None).unwrap()
+}
+
+/// A successful JSON-RPC response
+pub fn result(result: Value) -> (u16, String) {
+    (
+        200,
+        serde_json::json!({ "jsonrpc": "2.0", "result": result, "id": 1 }).to_string(),
+    )
+}
+
+/// A JSON-RPC error response
+pub fn error(error: Value) -> (u16, String) {
+    (
+        200,
+        serde_json::json!({ "jsonrpc": "2.0", "error": error, "id": 1 }).to_string(),
+    )
+}
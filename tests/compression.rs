@@ -0,0 +1,130 @@
+use poseidon_common::{
+    EncodedUserData, PoseidonError, UserData, UserDataEncoding, DEFAULT_MAX_DECODED_LEN,
+};
+
+fn text(len: usize) -> UserData {
+    UserData {
+        mime: "text/plain".to_owned(),
+        data: b"poseidon ".iter().copied().cycle().take(len).collect(),
+    }
+}
+
+fn is_invalid(outcome: Result<UserData, PoseidonError>) -> bool {
+    matches!(outcome, Err(PoseidonError::InvalidEncodedData(_)))
+}
+
+#[test]
+fn identity_round_trips() {
+    let data = text(100);
+    let encoded = data.encode(UserDataEncoding::Identity).unwrap();
+
+    assert_eq!(encoded.decoded_len, 100);
+    assert_eq!(encoded.data, data.data);
+
+    let decoded =
+        UserData::from_encoded_bytes(&encoded.to_bytes().unwrap(), DEFAULT_MAX_DECODED_LEN)
+            .unwrap();
+    assert_eq!(decoded.mime, data.mime);
+    assert_eq!(decoded.data, data.data);
+}
+
+#[test]
+fn the_size_ceiling_is_enforced_before_decoding() {
+    let encoded = text(100).encode(UserDataEncoding::Identity).unwrap();
+
+    assert!(encoded.clone().decode(100).is_ok());
+    assert_eq!(
+        encoded.decode(99).map(|_| ()),
+        Err(PoseidonError::DecodedSizeLimitExceeded { limit: 99 })
+    );
+
+    // The declared length is checked, not the length of the bytes
+    let bomb = EncodedUserData {
+        encoding: UserDataEncoding::Deflate,
+        mime: "text/plain".to_owned(),
+        decoded_len: u64::MAX,
+        data: Vec::new(),
+    };
+    assert_eq!(
+        bomb.decode(DEFAULT_MAX_DECODED_LEN).map(|_| ()),
+        Err(PoseidonError::DecodedSizeLimitExceeded {
+            limit: DEFAULT_MAX_DECODED_LEN
+        })
+    );
+}
+
+#[test]
+fn lying_identity_headers_are_rejected() {
+    let mut encoded = text(100).encode(UserDataEncoding::Identity).unwrap();
+    encoded.decoded_len = 99;
+    assert!(is_invalid(encoded.clone().decode(DEFAULT_MAX_DECODED_LEN)));
+
+    encoded.decoded_len = 101;
+    assert!(is_invalid(encoded.decode(DEFAULT_MAX_DECODED_LEN)));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn deflate_round_trips() {
+    let data = text(10_000);
+    let compressed = data.compress().unwrap();
+
+    assert_eq!(compressed.encoding, UserDataEncoding::Deflate);
+    assert_eq!(compressed.decoded_len, 10_000);
+    assert!(compressed.data.len() < 1_000);
+
+    let decoded = compressed.decode(10_000).unwrap();
+    assert_eq!(decoded.data, data.data);
+
+    // Incompressible data is kept as is
+    let tiny = text(3).compress().unwrap();
+    assert_eq!(tiny.encoding, UserDataEncoding::Identity);
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn lying_deflate_headers_are_rejected() {
+    // 1MiB of zeros compresses to about a kilobyte
+    let zeros = UserData {
+        mime: "application/octet-stream".to_owned(),
+        data: vec![0; 1024 * 1024],
+    };
+    let mut bomb = zeros.encode(UserDataEncoding::Deflate).unwrap();
+
+    // Claiming less than the real length stops inflating one byte past it
+    bomb.decoded_len = 1024;
+    match bomb.clone().decode(DEFAULT_MAX_DECODED_LEN) {
+        Err(PoseidonError::InvalidEncodedData(details)) => {
+            assert_eq!(details, "expected `1024` decoded bytes, found `1025`")
+        }
+        outcome => panic!("{:?}", outcome.map(|data| data.data.len())),
+    }
+
+    // Claiming more than the real length
+    bomb.decoded_len = 1024 * 1024 + 1;
+    assert!(is_invalid(bomb.clone().decode(DEFAULT_MAX_DECODED_LEN)));
+
+    // Garbage instead of a DEFLATE stream
+    bomb.data = vec![0xff; 16];
+    assert!(is_invalid(bomb.decode(DEFAULT_MAX_DECODED_LEN)));
+}
+
+#[cfg(not(feature = "compression"))]
+#[test]
+fn deflate_needs_the_compression_feature() {
+    assert!(matches!(
+        text(100).encode(UserDataEncoding::Deflate),
+        Err(PoseidonError::UnsupportedEncoding(_))
+    ));
+
+    let encoded = EncodedUserData {
+        encoding: UserDataEncoding::Deflate,
+        mime: "text/plain".to_owned(),
+        decoded_len: 0,
+        data: Vec::new(),
+    };
+    assert!(matches!(
+        encoded.decode(DEFAULT_MAX_DECODED_LEN),
+        Err(PoseidonError::UnsupportedEncoding(_))
+    ));
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "config")]
+
+use poseidon_common::{
+    default_keypair_path, Cluster, Commitment, ConfigFormat, PoseidonConfig, PoseidonError,
+};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+const SOLANA_CLI_CONFIG: &str = r#"---
+json_rpc_url: "http://127.0.0.1:8899"
+websocket_url: ""
+keypair_path: /home/poseidon/.config/solana/id.json
+address_labels:
+  "11111111111111111111111111111111": System Program
+commitment: singleGossip
+"#;
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("poseidon-config-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join(name);
+    fs::write(&path, contents).unwrap();
+
+    path
+}
+
+#[test]
+fn toml_and_json_files() {
+    let toml = r#"
+        cluster = "mainnet-beta"
+        keypair_path = "/etc/poseidon/id.json"
+        commitment = "finalized"
+    "#;
+    let expected = PoseidonConfig {
+        cluster: Cluster::MainnetBeta,
+        keypair_path: PathBuf::from("/etc/poseidon/id.json"),
+        commitment: Commitment::Finalized,
+    };
+    assert_eq!(
+        PoseidonConfig::parse(toml, ConfigFormat::Toml).unwrap(),
+        expected
+    );
+
+    let json = r#"{"rpc_url": "https://rpc.example.com", "ws_url": "wss://ws.example.com"}"#;
+    let config = PoseidonConfig::parse(json, ConfigFormat::Json).unwrap();
+    assert_eq!(
+        config.cluster,
+        Cluster::Custom {
+            rpc_url: "https://rpc.example.com".to_owned(),
+            ws_url: Some("wss://ws.example.com".to_owned()),
+        }
+    );
+    assert_eq!(config.keypair_path, default_keypair_path().unwrap());
+    assert_eq!(config.commitment, Commitment::Confirmed);
+}
+
+#[test]
+fn solana_cli_config() {
+    let config = PoseidonConfig::parse(SOLANA_CLI_CONFIG, ConfigFormat::SolanaCli).unwrap();
+
+    assert_eq!(
+        config,
+        PoseidonConfig {
+            cluster: Cluster::Localnet,
+            keypair_path: PathBuf::from("/home/poseidon/.config/solana/id.json"),
+            commitment: Commitment::Confirmed,
+        }
+    );
+}
+
+#[test]
+fn invalid_configs() {
+    for (contents, format) in [
+        ("cluster = \"mainnet-alpha\"", ConfigFormat::Toml),
+        ("commitment = \"eventually\"", ConfigFormat::Toml),
+        ("rpc_urll = \"https://rpc.example.com\"", ConfigFormat::Toml),
+        (
+            "{\"rpc_url\": \"ftp://rpc.example.com\"}",
+            ConfigFormat::Json,
+        ),
+    ] {
+        let error = PoseidonConfig::parse(contents, format).unwrap_err();
+        assert!(
+            matches!(
+                error,
+                PoseidonError::InvalidConfig(..) | PoseidonError::InvalidCluster(..)
+            ),
+            "{:?}",
+            error
+        );
+    }
+
+    assert!(ConfigFormat::from_path("config.ini").is_err());
+}
+
+#[test]
+fn environment_overrides_the_file() {
+    let path = temp_file("config.yml", SOLANA_CLI_CONFIG);
+    let mut env = HashMap::new();
+    env.insert("POSEIDON_CONFIG", path.to_str().unwrap().to_owned());
+    let load = |env: &HashMap<&str, String>| {
+        PoseidonConfig::load_with_env(|name| env.get(name).cloned()).unwrap()
+    };
+
+    assert_eq!(load(&env).cluster, Cluster::Localnet);
+
+    env.insert("POSEIDON_CLUSTER", "devnet".to_owned());
+    env.insert("POSEIDON_COMMITMENT", "processed".to_owned());
+    env.insert("POSEIDON_KEYPAIR", "/tmp/id.json".to_owned());
+    let config = load(&env);
+    assert_eq!(config.cluster, Cluster::Devnet);
+    assert_eq!(config.commitment, Commitment::Processed);
+    assert_eq!(config.keypair_path, PathBuf::from("/tmp/id.json"));
+
+    env.insert("POSEIDON_RPC_URL", "https://rpc.example.com".to_owned());
+    assert_eq!(
+        load(&env).cluster,
+        "https://rpc.example.com".parse::<Cluster>().unwrap()
+    );
+
+    env.insert("POSEIDON_CONFIG", "/nonexistent/config.toml".to_owned());
+    assert!(PoseidonConfig::load_with_env(|name| env.get(name).cloned()).is_err());
+
+    fs::remove_file(path).unwrap();
+}
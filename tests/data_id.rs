@@ -0,0 +1,87 @@
+use borsh::BorshSerialize;
+use poseidon_common::{
+    data_id_from_base58, data_id_from_borsh, data_id_to_base58, verify_data_id, PoseidonError,
+    UserData,
+};
+use sha2::{Digest, Sha256};
+
+/// `u32` length of the mime, the mime, `u32` length of the data, the data
+const PREIMAGE: &str = "0a000000746578742f706c61696e0500000068656c6c6f";
+const DATA_ID: &str = "924c3325c972af67d7160f5150997f2267635d61b7a6699a403ff1e391b5c171";
+
+fn hello() -> UserData {
+    UserData {
+        mime: "text/plain".to_owned(),
+        data: b"hello".to_vec(),
+    }
+}
+
+#[test]
+fn data_ids_are_stable() {
+    let data = hello();
+    let data_id = data.data_id().unwrap();
+
+    assert_eq!(hex::encode(data_id), DATA_ID);
+    assert_eq!(data.try_to_vec().unwrap(), hex::decode(PREIMAGE).unwrap());
+    assert_eq!(
+        data_id,
+        <[u8; 32]>::from(Sha256::digest(hex::decode(PREIMAGE).unwrap()))
+    );
+
+    assert_eq!(
+        data_id_from_borsh(&hex::decode(PREIMAGE).unwrap()),
+        Ok(data_id)
+    );
+    assert_eq!(
+        data_id_from_base58(&data_id_to_base58(&data_id)),
+        Ok(data_id)
+    );
+}
+
+#[test]
+fn data_ids_cover_mime_and_data() {
+    let data_id = hello().data_id().unwrap();
+
+    let mut other = hello();
+    other.mime = "text/html".to_owned();
+    assert_ne!(other.data_id().unwrap(), data_id);
+    assert_eq!(
+        other.verify_data_id(&data_id),
+        Err(PoseidonError::DataIdMismatch)
+    );
+
+    // Length prefixes keep the boundary between mime and data unambiguous
+    let shifted = UserData {
+        mime: "text/plainh".to_owned(),
+        data: b"ello".to_vec(),
+    };
+    assert_ne!(shifted.data_id().unwrap(), data_id);
+
+    assert_eq!(hello().verify_data_id(&data_id), Ok(()));
+}
+
+#[test]
+fn borsh_blobs_are_checked() {
+    let blob = hex::decode(PREIMAGE).unwrap();
+    let data_id = hello().data_id().unwrap();
+
+    assert_eq!(verify_data_id(&blob, &data_id), Ok(()));
+    assert_eq!(
+        verify_data_id(&blob, &[0; 32]),
+        Err(PoseidonError::DataIdMismatch)
+    );
+
+    let mut trailing = blob.clone();
+    trailing.push(0);
+    assert!(data_id_from_borsh(&trailing).is_err());
+    assert!(data_id_from_borsh(&blob[..blob.len() - 1]).is_err());
+
+    assert_eq!(
+        data_id_from_base58("not base58: 0OIl"),
+        Err(PoseidonError::InvalidBase58Sha256Hash)
+    );
+    assert_eq!(
+        data_id_from_base58(&bs58::encode([0; 31]).into_string()),
+        Err(PoseidonError::InvalidBase58Sha256Hash)
+    );
+}
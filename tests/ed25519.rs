@@ -0,0 +1,151 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use poseidon_common::{Ed25519Keypair, Ed25519PublicKey, Ed25519Signature, PoseidonError};
+use std::collections::BTreeMap;
+
+// RFC 8032 section 7.1, test 1
+const SECRET: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+const PUBLIC: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+const SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+fn rfc8032_keypair() -> Ed25519Keypair {
+    let seed: [u8; 32] = hex::decode(SECRET).unwrap().try_into().unwrap();
+
+    Ed25519Keypair::from_seed(&seed)
+}
+
+#[test]
+fn rfc8032_test_1() {
+    let keypair = rfc8032_keypair();
+    let signature = keypair.sign(b"");
+
+    assert_eq!(hex::encode(keypair.public_key()), PUBLIC);
+    assert_eq!(hex::encode(signature), SIGNATURE);
+    assert_eq!(keypair.verify(b"", &signature), Ok(()));
+    assert_eq!(
+        keypair.verify(b"tampered", &signature),
+        Err(PoseidonError::SignatureVerificationFailed)
+    );
+    assert_eq!(
+        Ed25519Keypair::from_seed(&[1; 32]).verify(b"", &signature),
+        Err(PoseidonError::SignatureVerificationFailed)
+    );
+}
+
+#[test]
+fn base58_and_borsh_round_trips() {
+    let keypair = rfc8032_keypair();
+    let public_key = keypair.public_key();
+    let signature = keypair.sign(b"message");
+
+    assert_eq!(
+        public_key.to_base58().parse::<Ed25519PublicKey>(),
+        Ok(public_key)
+    );
+    assert_eq!(
+        signature.to_base58().parse::<Ed25519Signature>(),
+        Ok(signature)
+    );
+    assert_eq!(
+        Ed25519Keypair::from_base58(&keypair.to_base58()),
+        Ok(rfc8032_keypair())
+    );
+
+    let encoded = public_key.try_to_vec().unwrap();
+    assert_eq!(encoded, public_key.to_bytes());
+    assert_eq!(
+        Ed25519PublicKey::try_from_slice(&encoded).unwrap(),
+        public_key
+    );
+    let encoded = signature.try_to_vec().unwrap();
+    assert_eq!(
+        Ed25519Signature::try_from_slice(&encoded).unwrap(),
+        signature
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn serde_round_trips() {
+    let keypair = rfc8032_keypair();
+    let public_key = keypair.public_key();
+    let signature = keypair.sign(b"message");
+
+    let json = serde_json::to_string(&public_key).unwrap();
+    assert_eq!(json, format!("\"{}\"", public_key.to_base58()));
+    assert_eq!(
+        serde_json::from_str::<Ed25519PublicKey>(&json).unwrap(),
+        public_key
+    );
+
+    let json = serde_json::to_string(&signature).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Ed25519Signature>(&json).unwrap(),
+        signature
+    );
+
+    assert!(serde_json::from_str::<Ed25519PublicKey>("[1, 2, 3]").is_err());
+    assert!(serde_json::from_str::<Ed25519Signature>(&format!("\"{}\"", public_key)).is_err());
+    assert!(serde_json::from_str::<Ed25519PublicKey>("\"0OIl\"").is_err());
+}
+
+#[test]
+fn lengths_are_checked() {
+    assert_eq!(
+        Ed25519PublicKey::try_from(&[0u8; 31][..]),
+        Err(PoseidonError::InvalidBase58Ed25519PublicKey)
+    );
+    assert_eq!(
+        Ed25519PublicKey::from_base58(&bs58::encode([0u8; 33]).into_string()),
+        Err(PoseidonError::InvalidBase58Ed25519PublicKey)
+    );
+    assert_eq!(
+        Ed25519Signature::try_from(&[0u8; 32][..]),
+        Err(PoseidonError::InvalidBase58Ed25519Signature)
+    );
+    assert_eq!(
+        Ed25519Signature::from_base58("not base58: 0OIl"),
+        Err(PoseidonError::InvalidBase58Ed25519Signature)
+    );
+    assert_eq!(
+        Ed25519Keypair::from_bytes(&[0u8; 32]),
+        Err(PoseidonError::InvalidBase58Ed25519SecretKey)
+    );
+}
+
+#[test]
+fn keypair_bytes_must_match_the_public_key() {
+    let keypair = rfc8032_keypair();
+    let mut bytes = *keypair.to_bytes();
+
+    assert_eq!(Ed25519Keypair::from_bytes(&bytes), Ok(rfc8032_keypair()));
+
+    bytes[32..].copy_from_slice(Ed25519Keypair::from_seed(&[1; 32]).public_key().as_bytes());
+    assert_eq!(
+        Ed25519Keypair::from_bytes(&bytes),
+        Err(PoseidonError::InvalidBase58Ed25519SecretKey)
+    );
+}
+
+#[test]
+fn keypair_debug_hides_the_secret() {
+    let keypair = rfc8032_keypair();
+    let debug = format!("{:?}", keypair);
+
+    assert_eq!(
+        debug,
+        format!("Ed25519Keypair(Ed25519PublicKey({}))", keypair.public_key())
+    );
+    assert!(!debug.contains(keypair.to_base58().as_str()));
+    assert!(!debug.contains(&bs58::encode(*keypair.seed()).into_string()));
+}
+
+#[test]
+fn keys_and_signatures_are_ordered_by_bytes() {
+    let low = Ed25519PublicKey::new([0; 32]);
+    let high = Ed25519PublicKey::new([1; 32]);
+    assert!(low < high);
+    assert!(Ed25519Signature::new([0; 64]) < Ed25519Signature::new([1; 64]));
+
+    let balances: BTreeMap<_, _> = [(high, 2), (low, 1)].into_iter().collect();
+    assert_eq!(balances.keys().collect::<Vec<_>>(), [&low, &high]);
+}
@@ -0,0 +1,387 @@
+//! How errors are returned by gateways and read back by clients
+
+use core::time::Duration;
+use poseidon_common::{
+    borsh_from_slice, ErrorClass, HttpError, InstructionError, JsonError, PoseidonError,
+    PoseidonErrorKind, PoseidonIoError, RawOsError, RustlsError, SctError, StoreErr,
+    TransactionError, MAX_BACKOFF,
+};
+use std::error::Error;
+
+#[test]
+fn poseidon_errors_use_their_own_range() {
+    let error = JsonError::from(PoseidonError::MissingKeypair.context("signing"));
+    assert_eq!(error.code, -21001);
+    assert_eq!(error.message, "signing: missing keypair");
+    assert_eq!(PoseidonError::from(error), PoseidonError::MissingKeypair);
+
+    // Fields are not part of the JSON-RPC error, the code and message remain
+    let error = JsonError::from(PoseidonError::ChunkOutOfOrder {
+        expected: 1,
+        found: 3,
+    });
+    assert_eq!(error.code, -22003);
+    assert_eq!(
+        PoseidonError::from(error),
+        PoseidonError::Unknown {
+            code: 2003,
+            message: "expected chunk 1 but received chunk 3".to_owned()
+        }
+    );
+}
+
+#[test]
+fn reserved_codes_are_kept() {
+    let error = JsonError::from(PoseidonError::Unspecified("oops".to_owned()));
+    assert_eq!(error.code, -32603);
+    assert_eq!(error.message, "oops");
+
+    let node_error = JsonError {
+        code: -32005,
+        message: "Node is unhealthy".to_owned(),
+        data: None,
+    };
+    let error = JsonError::from(PoseidonError::from(JsonError {
+        code: -32005,
+        message: "Node is unhealthy".to_owned(),
+        data: None,
+    }));
+    assert_eq!(error, node_error);
+}
+
+#[test]
+fn transaction_errors_are_sent_in_data() {
+    let transaction_error = TransactionError::InstructionError(0, InstructionError::Custom(6001));
+    let error = JsonError::from(PoseidonError::Tx(transaction_error.clone()));
+
+    assert_eq!(error.code, -32002);
+    assert_eq!(error.transaction_error(), Some(&transaction_error));
+    assert_eq!(
+        PoseidonError::from(error),
+        PoseidonError::Tx(transaction_error)
+    );
+}
+
+#[test]
+fn http_statuses() {
+    let not_found = PoseidonIoError::from(PoseidonErrorKind::NotFound);
+    let cases = vec![
+        (PoseidonError::AccountNotFound, 404),
+        (
+            PoseidonError::Store(StoreErr::StoreNotFound("blobs".to_owned())),
+            404,
+        ),
+        (
+            PoseidonError::IoErr(not_found).context("reading keypair"),
+            404,
+        ),
+        (PoseidonError::Store(StoreErr::PermissionDenied), 403),
+        (PoseidonError::RepoAlreadyExists, 409),
+        (PoseidonError::Tx(TransactionError::ClusterMaintenance), 503),
+        (PoseidonError::Tx(TransactionError::BlockhashNotFound), 503),
+        (
+            PoseidonError::Tx(TransactionError::InsufficientFundsForFee),
+            400,
+        ),
+        (PoseidonError::InvalidBase58Ed25519PublicKey, 400),
+        (PoseidonError::DataIdMismatch, 500),
+    ];
+
+    for (error, status) in cases {
+        assert_eq!(error.http_status(), status, "{:?}", error);
+    }
+}
+
+#[test]
+fn http_statuses_read_back_with_the_same_class() {
+    assert_eq!(PoseidonError::from_http_status(200, ""), None);
+
+    for status in [400, 403, 404, 409, 413, 500, 503, 504] {
+        let error = PoseidonError::from_http_status(status, "").unwrap();
+        assert_eq!(error.http_status(), status, "{:?}", error);
+    }
+
+    let error = PoseidonError::from_http_status(503, "try again later").unwrap();
+    assert!(error.is_retryable());
+    assert_eq!(error.to_string(), "I/O error: try again later");
+}
+
+#[test]
+fn display_and_source_follow_the_chain() {
+    let error = PoseidonError::MissingKeypair
+        .context("reading keypair")
+        .context("signing transaction");
+    assert_eq!(
+        error.to_string(),
+        "signing transaction: reading keypair: missing keypair"
+    );
+    assert_eq!(
+        error.context_chain(),
+        ["reading keypair", "signing transaction"]
+    );
+    assert_eq!(error.root_cause(), &PoseidonError::MissingKeypair);
+
+    let source = error.source().unwrap();
+    assert_eq!(source.to_string(), "missing keypair");
+    assert!(source.source().is_none());
+
+    let error = PoseidonError::IoErr(
+        PoseidonIoError::from(PoseidonErrorKind::NotFound).with_path("/tmp/id.json"),
+    );
+    assert_eq!(
+        error.to_string(),
+        "I/O error: `/tmp/id.json`: entity not found"
+    );
+    assert_eq!(
+        error.source().unwrap().to_string(),
+        "`/tmp/id.json`: entity not found"
+    );
+
+    let error = PoseidonError::Tx(TransactionError::InstructionError(
+        1,
+        InstructionError::Custom(6001),
+    ));
+    assert_eq!(
+        error.to_string(),
+        "transaction error: Error processing Instruction 1: custom program error: 0x1771"
+    );
+    assert!(error.source().is_some());
+
+    // Errors nested two levels deep
+    let error = PoseidonError::Rustls(RustlsError::InvalidSct(SctError::MalformedSct));
+    let chain: Vec<String> =
+        std::iter::successors(Some(&error as &dyn Error), |error| (*error).source())
+            .map(|error| error.to_string())
+            .collect();
+    assert_eq!(
+        chain,
+        [
+            "TLS error: invalid certificate timestamp: malformed SCT",
+            "invalid certificate timestamp: malformed SCT",
+            "malformed SCT",
+        ]
+    );
+
+    // Errors without fields have no source
+    assert!(PoseidonError::AccountNotFound.source().is_none());
+    assert_eq!(
+        PoseidonError::AccountNotFound.to_string(),
+        "account not found"
+    );
+}
+
+#[test]
+fn errors_are_classified() {
+    let json = |code| {
+        PoseidonError::Json(JsonError {
+            code,
+            message: String::new(),
+            data: None,
+        })
+    };
+    let io = |kind| PoseidonError::IoErr(PoseidonIoError::from(kind));
+    let cases = vec![
+        (io(PoseidonErrorKind::TimedOut), ErrorClass::Transient),
+        (
+            io(PoseidonErrorKind::ConnectionReset),
+            ErrorClass::Transient,
+        ),
+        (io(PoseidonErrorKind::NotFound), ErrorClass::User),
+        (io(PoseidonErrorKind::Other), ErrorClass::Permanent),
+        (
+            PoseidonError::Tx(TransactionError::BlockhashNotFound),
+            ErrorClass::Transient,
+        ),
+        (
+            PoseidonError::Tx(TransactionError::InsufficientFundsForFee),
+            ErrorClass::User,
+        ),
+        (
+            PoseidonError::Tx(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(1),
+            )),
+            ErrorClass::Permanent,
+        ),
+        (
+            PoseidonError::Http(HttpError::AddressNotFound),
+            ErrorClass::Transient,
+        ),
+        (PoseidonError::Http(HttpError::BadProxy), ErrorClass::User),
+        (
+            PoseidonError::Http(HttpError::TooManyRedirections),
+            ErrorClass::Permanent,
+        ),
+        (json(-32005), ErrorClass::Transient),
+        (json(-32601), ErrorClass::User),
+        (json(-32002), ErrorClass::Permanent),
+        (PoseidonError::NoHealthyEndpoint, ErrorClass::Transient),
+        (
+            PoseidonError::InvalidBase58Ed25519PublicKey,
+            ErrorClass::User,
+        ),
+        (PoseidonError::KeystoreWrongPassword, ErrorClass::User),
+        (PoseidonError::DataIdMismatch, ErrorClass::Permanent),
+        (
+            PoseidonError::Unspecified("oops".to_owned()),
+            ErrorClass::Permanent,
+        ),
+    ];
+
+    for (error, class) in cases {
+        assert_eq!(error.class(), class, "{:?}", error);
+        assert_eq!(error.is_retryable(), class == ErrorClass::Transient);
+        assert_eq!(error.is_permanent(), class != ErrorClass::Transient);
+
+        // Context does not change the class
+        let error = error.context("retrying");
+        assert_eq!(error.class(), class, "{:?}", error);
+    }
+}
+
+#[test]
+fn backoff_doubles_up_to_the_maximum() {
+    let error = PoseidonError::Tx(TransactionError::BlockhashNotFound).context("sending");
+    assert_eq!(error.retry_after(), Some(Duration::from_millis(400)));
+    assert_eq!(error.backoff(0), Some(Duration::from_millis(400)));
+    assert_eq!(error.backoff(1), Some(Duration::from_millis(800)));
+    assert_eq!(error.backoff(3), Some(Duration::from_millis(3_200)));
+    assert_eq!(error.backoff(8), Some(MAX_BACKOFF));
+    assert_eq!(error.backoff(u32::MAX), Some(MAX_BACKOFF));
+
+    let maintenance = PoseidonError::Tx(TransactionError::ClusterMaintenance);
+    assert_eq!(maintenance.backoff(0), Some(Duration::from_secs(30)));
+    assert_eq!(maintenance.backoff(1), Some(MAX_BACKOFF));
+
+    // Errors without a specific delay start at half a second
+    let timeout = PoseidonError::IoErr(PoseidonIoError::from(PoseidonErrorKind::TimedOut));
+    assert_eq!(timeout.backoff(0), Some(Duration::from_millis(500)));
+    assert_eq!(timeout.backoff(2), Some(Duration::from_secs(2)));
+
+    // Permanent errors are not retried at all
+    let permanent = PoseidonError::Tx(TransactionError::AccountNotFound);
+    assert_eq!(permanent.retry_after(), None);
+    assert_eq!(permanent.backoff(0), None);
+    assert_eq!(PoseidonError::DataIdMismatch.backoff(0), None);
+}
+
+#[test]
+fn foreign_errors_keep_their_details() {
+    let utf8 = |bytes: &[u8]| PoseidonError::from(std::str::from_utf8(bytes).unwrap_err());
+    assert_eq!(
+        utf8(b"ab\xffcd"),
+        PoseidonError::InvalidUtf8Sequence {
+            valid_up_to: 2,
+            error_len: Some(1)
+        }
+    );
+    // Input that ends in the middle of a character
+    assert_eq!(
+        utf8(b"ab\xe2\x82"),
+        PoseidonError::InvalidUtf8Sequence {
+            valid_up_to: 2,
+            error_len: None
+        }
+    );
+    assert_eq!(
+        PoseidonError::from(String::from_utf8(b"ab\xffcd".to_vec()).unwrap_err()),
+        utf8(b"ab\xffcd")
+    );
+
+    let base58 = |value: &str| PoseidonError::from(bs58::decode(value).into_vec().unwrap_err());
+    assert_eq!(
+        base58("2Ol"),
+        PoseidonError::InvalidBase58Character {
+            c: "O".to_owned(),
+            index: 1
+        }
+    );
+    assert_eq!(
+        base58("2\u{e9}"),
+        PoseidonError::NonAsciiBase58Character { index: 1 }
+    );
+    assert_eq!(
+        PoseidonError::from(bs58::decode("2").into(&mut [0u8; 0]).unwrap_err()),
+        PoseidonError::InvalidBase58Length
+    );
+
+    assert_eq!(
+        PoseidonError::from(<[u8; 4]>::try_from(&[1u8, 2, 3][..]).unwrap_err()),
+        PoseidonError::InvalidSliceLength
+    );
+
+    assert_eq!(
+        PoseidonError::from(hex::decode("0g").unwrap_err()),
+        PoseidonError::InvalidHexCharacter {
+            c: "g".to_owned(),
+            index: 1
+        }
+    );
+    assert_eq!(
+        PoseidonError::from(hex::decode("abc").unwrap_err()),
+        PoseidonError::OddLength
+    );
+    assert_eq!(
+        PoseidonError::from(hex::decode_to_slice("abcd", &mut [0; 1]).unwrap_err()),
+        PoseidonError::InvalidStringLength
+    );
+}
+
+#[test]
+fn io_errors_keep_kind_and_message() {
+    let error = PoseidonError::from(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        "no answer",
+    ));
+    assert_eq!(
+        error,
+        PoseidonError::IoErr(PoseidonIoError {
+            kind: PoseidonErrorKind::TimedOut,
+            raw_os_error: None,
+            message: "no answer".to_owned(),
+            path: None,
+        })
+    );
+
+    #[cfg(unix)]
+    {
+        // ENOENT
+        let error = PoseidonError::from(std::io::Error::from_raw_os_error(2));
+        match error {
+            PoseidonError::IoErr(error) => {
+                assert_eq!(error.kind, PoseidonErrorKind::NotFound);
+                assert_eq!(error.raw_os_error, Some(RawOsError::new(2)));
+            }
+            error => panic!("{:?}", error),
+        }
+    }
+}
+
+#[test]
+fn borsh_failures_are_not_io_errors() {
+    assert_eq!(borsh_from_slice::<u32>(&[1, 0, 0, 0]), Ok(1));
+
+    // Too short and trailing bytes
+    for bytes in [&[1, 0][..], &[1, 0, 0, 0, 0]] {
+        assert!(
+            matches!(
+                borsh_from_slice::<u32>(bytes),
+                Err(PoseidonError::BorshDeserializationFailed(_))
+            ),
+            "{:?}",
+            bytes
+        );
+    }
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn serde_json_errors_keep_their_message() {
+    let error = serde_json::from_str::<u32>("\"one\"").unwrap_err();
+    let message = error.to_string();
+
+    assert_eq!(
+        PoseidonError::from(error),
+        PoseidonError::SerdeJson(message)
+    );
+}
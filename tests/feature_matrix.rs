@@ -0,0 +1,77 @@
+//! Builds and tests the crate with no features, with every feature on its own,
+//! with every pair of features and with all features. The error model is the
+//! same in every build, so running `wire_compat` under each of them checks
+//! that all builds agree on the encoding.
+//!
+//! This rebuilds the crate close to seventy times, so it is ignored by
+//! default. CI runs it with `cargo test --test feature_matrix -- --ignored`.
+
+use std::{path::Path, process::Command};
+
+const FEATURES: &[&str] = &[
+    "sled_kv",
+    "solana_client",
+    "rustls",
+    "http",
+    "serde_json",
+    "keypair_file",
+    "config",
+    "mnemonic",
+    "keystore",
+    "compression",
+    "sealed_box",
+];
+
+fn cargo(args: &[&str], features: &[&str], extra_args: &[&str]) {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let status = Command::new(env!("CARGO"))
+        .args(args)
+        .arg("--no-default-features")
+        .arg("--features")
+        .arg(features.join(","))
+        .args(extra_args)
+        .current_dir(manifest_dir)
+        // A separate target directory keeps the outer `cargo test` lock free
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(manifest_dir)
+                .join("target")
+                .join("feature-matrix"),
+        )
+        .status()
+        .expect("cargo can be spawned");
+
+    assert!(
+        status.success(),
+        "`cargo {}` failed with features {:?}",
+        args.join(" "),
+        features
+    );
+}
+
+/// The empty set, every feature, every pair of features and all features
+fn feature_sets() -> Vec<Vec<&'static str>> {
+    let mut feature_sets = vec![Vec::new()];
+    for (index, first) in FEATURES.iter().enumerate() {
+        feature_sets.push(vec![*first]);
+        for second in &FEATURES[index + 1..] {
+            feature_sets.push(vec![*first, *second]);
+        }
+    }
+    feature_sets.push(FEATURES.to_vec());
+
+    feature_sets
+}
+
+#[test]
+#[ignore]
+fn feature_pairs_build_and_pass() {
+    for features in feature_sets() {
+        cargo(
+            &["clippy", "--all-targets"],
+            &features,
+            &["--", "-D", "warnings"],
+        );
+        cargo(&["test", "--tests"], &features, &[]);
+    }
+}
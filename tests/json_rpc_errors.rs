@@ -0,0 +1,87 @@
+#![cfg(feature = "serde_json")]
+
+use poseidon_common::{InstructionError, JsonError, PoseidonError, TransactionError};
+
+fn transaction_error(json: &str) -> TransactionError {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn instruction_errors_are_parsed() {
+    assert_eq!(
+        transaction_error(r#"{"InstructionError":[0,{"Custom":6001}]}"#),
+        TransactionError::InstructionError(0, InstructionError::Custom(6001))
+    );
+    assert_eq!(
+        transaction_error(r#"{"InstructionError":[2,"InvalidAccountData"]}"#),
+        TransactionError::InstructionError(2, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        transaction_error(r#""BlockhashNotFound""#),
+        TransactionError::BlockhashNotFound
+    );
+}
+
+#[test]
+fn both_forms_of_borsh_io_errors_are_parsed() {
+    // Older nodes send the message, newer ones only the name
+    assert_eq!(
+        transaction_error(r#"{"InstructionError":[1,{"BorshIoError":"Unexpected length"}]}"#),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::BorshIoError("Unexpected length".to_owned())
+        )
+    );
+    assert_eq!(
+        transaction_error(r#"{"InstructionError":[1,"BorshIoError"]}"#),
+        TransactionError::InstructionError(1, InstructionError::BorshIoError(String::new()))
+    );
+}
+
+#[test]
+fn preflight_failures_become_transaction_errors() {
+    let error: JsonError = serde_json::from_str(
+        r#"{
+            "code": -32002,
+            "message": "Transaction simulation failed: Error processing Instruction 0: custom program error: 0x1771",
+            "data": {
+                "accounts": null,
+                "err": {"InstructionError": [0, {"Custom": 6001}]},
+                "logs": ["Program log: Error: slippage exceeded"],
+                "unitsConsumed": 2000
+            }
+        }"#,
+    )
+    .unwrap();
+    let expected = TransactionError::InstructionError(0, InstructionError::Custom(6001));
+
+    assert_eq!(error.transaction_error(), Some(&expected));
+    let data = error.data.as_ref().unwrap();
+    assert_eq!(
+        data.logs.as_deref(),
+        Some(&["Program log: Error: slippage exceeded".to_owned()][..])
+    );
+    assert_eq!(data.units_consumed, Some(2000));
+    assert_eq!(PoseidonError::from(error), PoseidonError::Tx(expected));
+}
+
+#[test]
+fn unrelated_data_is_ignored() {
+    let cases = [
+        r#"{"code":-32005,"message":"Node is behind by 42 slots","data":{"numSlotsBehind":42}}"#,
+        r#"{"code":-32002,"message":"Transaction simulation failed","data":{"err":"SomethingNew"}}"#,
+        r#"{"code":-32603,"message":"Internal error","data":"details"}"#,
+        r#"{"code":-32601,"message":"Method not found"}"#,
+    ];
+
+    for json in cases {
+        let error: JsonError = serde_json::from_str(json).unwrap();
+
+        assert_eq!(error.transaction_error(), None, "{}", json);
+        assert!(
+            matches!(PoseidonError::from(error), PoseidonError::Json(_)),
+            "{}",
+            json
+        );
+    }
+}
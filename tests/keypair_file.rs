@@ -0,0 +1,143 @@
+#![cfg(feature = "keypair_file")]
+
+use poseidon_common::{
+    default_keypair_path, expand_home_path, keypair_from_json, keypair_to_json,
+    read_default_keypair, read_keypair_file, resolve_keypair_path, write_default_keypair,
+    write_keypair_file, Ed25519Keypair, PoseidonError, PoseidonErrorKind, DEFAULT_KEYPAIR_PATH,
+};
+use std::{fs, path::PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "poseidon-keypair-file-{}-{}",
+        std::process::id(),
+        name
+    ));
+    let _ = fs::remove_dir_all(&dir);
+
+    dir
+}
+
+#[test]
+fn keypair_files_round_trip() {
+    let keypair = Ed25519Keypair::from_seed(&[7; 32]);
+    let path = temp_dir("round-trip").join("nested/id.json");
+
+    assert_eq!(write_keypair_file(&keypair, &path).unwrap(), path);
+    assert_eq!(read_keypair_file(&path).unwrap(), keypair);
+
+    // The solana-keygen layout: a JSON array of the 64 keypair bytes
+    let json = fs::read_to_string(&path).unwrap();
+    let bytes: Vec<u8> = serde_json::from_str(&json).unwrap();
+    assert_eq!(bytes, keypair.to_bytes().to_vec());
+    assert_eq!(*keypair_to_json(&keypair).unwrap(), json);
+}
+
+#[cfg(unix)]
+#[test]
+fn keypair_files_are_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = temp_dir("permissions").join("id.json");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+    // Existing files are tightened as well
+    fs::write(&path, "").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+    write_keypair_file(&Ed25519Keypair::generate(), &path).unwrap();
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn malformed_keypairs_are_rejected() {
+    for json in ["", "{}", "[1, 2, 3", "[256, 0]", r#"["a"]"#] {
+        assert!(
+            matches!(keypair_from_json(json), Err(PoseidonError::SerdeJson(_))),
+            "{}",
+            json
+        );
+    }
+
+    // Valid JSON, but not a keypair
+    let short = serde_json::to_string(&[0u8; 32]).unwrap();
+    assert_eq!(
+        keypair_from_json(&short),
+        Err(PoseidonError::InvalidBase58Ed25519SecretKey)
+    );
+
+    let mut bytes = *Ed25519Keypair::from_seed(&[7; 32]).to_bytes();
+    bytes[63] ^= 1;
+    let mismatched = serde_json::to_string(bytes.as_slice()).unwrap();
+    assert_eq!(
+        keypair_from_json(&mismatched),
+        Err(PoseidonError::InvalidBase58Ed25519SecretKey)
+    );
+}
+
+#[test]
+fn read_errors_keep_the_path() {
+    let path = temp_dir("missing").join("id.json");
+
+    match read_keypair_file(&path) {
+        Err(PoseidonError::IoErr(error)) => {
+            assert_eq!(error.kind, PoseidonErrorKind::NotFound);
+            assert_eq!(error.path.as_deref(), path.to_str());
+        }
+        outcome => panic!("{:?}", outcome),
+    }
+}
+
+/// The only test of this file that touches `HOME`, so that it does not race
+/// with the others
+#[cfg(unix)]
+#[test]
+fn home_directory_paths() {
+    let home = temp_dir("home");
+    fs::create_dir_all(&home).unwrap();
+    std::env::set_var("HOME", &home);
+
+    assert_eq!(resolve_keypair_path("~").unwrap(), home);
+    assert_eq!(
+        resolve_keypair_path("~/keys/id.json").unwrap(),
+        home.join("keys/id.json")
+    );
+    // Only a leading `~` refers to the home directory
+    assert_eq!(
+        resolve_keypair_path("keys/~/id.json").unwrap(),
+        PathBuf::from("keys/~/id.json")
+    );
+    assert_eq!(
+        resolve_keypair_path("~user/id.json").unwrap(),
+        PathBuf::from("~user/id.json")
+    );
+    assert_eq!(
+        expand_home_path("~/.config/poseidon/config.toml").unwrap(),
+        home.join(".config/poseidon/config.toml")
+    );
+    assert_eq!(
+        default_keypair_path().unwrap(),
+        home.join(DEFAULT_KEYPAIR_PATH)
+    );
+
+    assert_eq!(read_default_keypair(), Err(PoseidonError::MissingKeypair));
+
+    let keypair = Ed25519Keypair::from_seed(&[7; 32]);
+    assert_eq!(
+        write_default_keypair(&keypair).unwrap(),
+        home.join(DEFAULT_KEYPAIR_PATH)
+    );
+    assert_eq!(read_default_keypair().unwrap(), keypair);
+    assert_eq!(
+        read_keypair_file("~/.config/solana/id.json").unwrap(),
+        keypair
+    );
+
+    // Only a missing file means there is no keypair yet
+    fs::write(home.join(DEFAULT_KEYPAIR_PATH), "[1, 2, 3]").unwrap();
+    assert_eq!(
+        read_default_keypair(),
+        Err(PoseidonError::InvalidBase58Ed25519SecretKey)
+    );
+}
@@ -0,0 +1,169 @@
+#![cfg(feature = "keystore")]
+
+use poseidon_common::{
+    load_keystore, Ed25519Keypair, EncryptedKeystore, KeystoreKdfParams, PoseidonError,
+};
+use std::fs;
+
+const PASSWORD: &[u8] = b"correct horse battery staple";
+
+/// Cheap parameters so the tests do not spend their time in scrypt
+const FAST: KeystoreKdfParams = KeystoreKdfParams {
+    log_n: 10,
+    r: 8,
+    p: 1,
+};
+
+fn encrypted_keystore() -> (Ed25519Keypair, EncryptedKeystore) {
+    let keypair = Ed25519Keypair::from_seed(&[9; 32]);
+    let keystore = EncryptedKeystore::encrypt_with_params(&keypair, PASSWORD, FAST).unwrap();
+
+    (keypair, keystore)
+}
+
+fn is_corrupted<T: std::fmt::Debug>(outcome: Result<T, PoseidonError>) -> bool {
+    matches!(outcome, Err(PoseidonError::KeystoreCorrupted(_)))
+}
+
+#[test]
+fn keystores_round_trip() {
+    let (keypair, keystore) = encrypted_keystore();
+
+    assert_eq!(keystore.public_key(), keypair.public_key());
+    assert_eq!(keystore.decrypt(PASSWORD).unwrap(), keypair);
+
+    let decoded = EncryptedKeystore::from_bytes(&keystore.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded, keystore);
+    assert_eq!(decoded.decrypt(PASSWORD).unwrap(), keypair);
+
+    // Fresh salt and nonce every time
+    let (_, other) = encrypted_keystore();
+    assert_ne!(other.header.salt, keystore.header.salt);
+    assert_ne!(other.ciphertext, keystore.ciphertext);
+}
+
+#[test]
+fn keystore_files_round_trip() {
+    let path = std::env::temp_dir()
+        .join(format!("poseidon-keystore-{}", std::process::id()))
+        .join("id.keystore");
+    let (keypair, keystore) = encrypted_keystore();
+
+    keystore.save(&path).unwrap();
+    assert_eq!(EncryptedKeystore::load(&path).unwrap(), keystore);
+    assert_eq!(load_keystore(&path, PASSWORD).unwrap(), keypair);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn wrong_passwords_are_told_apart_from_corruption() {
+    let (_, keystore) = encrypted_keystore();
+
+    assert_eq!(
+        keystore.decrypt(b"wrong password"),
+        Err(PoseidonError::KeystoreWrongPassword)
+    );
+    assert_eq!(
+        keystore.decrypt(b""),
+        Err(PoseidonError::KeystoreWrongPassword)
+    );
+
+    // The last 16 bytes are the Poly1305 tag
+    let mut tampered = keystore.clone();
+    *tampered.ciphertext.last_mut().unwrap() ^= 1;
+    assert!(is_corrupted(tampered.decrypt(PASSWORD)));
+
+    let mut tampered = keystore.clone();
+    tampered.ciphertext[0] ^= 1;
+    assert!(is_corrupted(tampered.decrypt(PASSWORD)));
+
+    let mut tampered = keystore.clone();
+    tampered.ciphertext.truncate(8);
+    assert!(is_corrupted(tampered.decrypt(PASSWORD)));
+
+    // The header is authenticated as associated data
+    let mut tampered = keystore.clone();
+    tampered.header.public_key = Ed25519Keypair::from_seed(&[1; 32]).public_key();
+    assert!(is_corrupted(tampered.decrypt(PASSWORD)));
+
+    let bytes = keystore.to_bytes().unwrap();
+    assert!(is_corrupted(EncryptedKeystore::from_bytes(&bytes[..100])));
+    assert!(is_corrupted(EncryptedKeystore::from_bytes(b"PSDNKEYS")));
+
+    let mut bytes = bytes;
+    bytes[0] = b'X';
+    assert!(is_corrupted(EncryptedKeystore::from_bytes(&bytes)));
+}
+
+#[test]
+fn unsupported_versions_are_rejected() {
+    let (_, mut keystore) = encrypted_keystore();
+    keystore.header.version = 2;
+
+    assert_eq!(
+        EncryptedKeystore::from_bytes(&keystore.to_bytes().unwrap()),
+        Err(PoseidonError::KeystoreUnsupportedVersion(2))
+    );
+    assert_eq!(
+        keystore.decrypt(PASSWORD),
+        Err(PoseidonError::KeystoreUnsupportedVersion(2))
+    );
+}
+
+#[test]
+fn costly_kdf_parameters_are_rejected() {
+    let keypair = Ed25519Keypair::from_seed(&[9; 32]);
+    let rejected = [
+        // 4GiB of memory
+        KeystoreKdfParams {
+            log_n: 20,
+            r: 32,
+            p: 1,
+        },
+        // 512MiB of memory
+        KeystoreKdfParams {
+            log_n: 19,
+            r: 8,
+            p: 1,
+        },
+        // 128MiB of memory, but 16 passes over it
+        KeystoreKdfParams {
+            log_n: 17,
+            r: 8,
+            p: 16,
+        },
+        // Overflows the memory computation
+        KeystoreKdfParams {
+            log_n: 64,
+            r: 8,
+            p: 1,
+        },
+        KeystoreKdfParams {
+            log_n: 10,
+            r: u32::MAX,
+            p: u32::MAX,
+        },
+    ];
+
+    for params in rejected {
+        assert!(
+            is_corrupted(EncryptedKeystore::encrypt_with_params(
+                &keypair, PASSWORD, params
+            )),
+            "{:?}",
+            params
+        );
+
+        // A hostile file is rejected before scrypt runs
+        let (_, mut keystore) = encrypted_keystore();
+        keystore.header.kdf_params = params;
+        assert!(is_corrupted(keystore.decrypt(PASSWORD)), "{:?}", params);
+    }
+}
@@ -0,0 +1,211 @@
+use poseidon_common::{sniff_mime, MimeType, PoseidonError, UserData};
+
+fn parse(value: &str) -> MimeType {
+    value.parse().unwrap()
+}
+
+fn sniffed(bytes: &[u8]) -> Option<String> {
+    sniff_mime(bytes).map(|mime| mime.essence())
+}
+
+#[test]
+fn media_types_are_parsed() {
+    let mime = parse("text/plain");
+    assert_eq!(mime.top_level(), "text");
+    assert_eq!(mime.subtype(), "plain");
+    assert!(mime.parameters().is_empty());
+
+    let mime = parse("application/vnd.api+json");
+    assert_eq!(mime.essence(), "application/vnd.api+json");
+}
+
+#[test]
+fn names_are_case_insensitive_but_values_are_kept() {
+    let mime = parse("Text/HTML; Charset=UTF-8");
+
+    assert_eq!(mime.essence(), "text/html");
+    assert_eq!(
+        mime.parameters(),
+        [("charset".to_owned(), "UTF-8".to_owned())]
+    );
+    assert_eq!(mime.parameter("CHARSET"), Some("UTF-8"));
+    assert_eq!(mime.parameter("boundary"), None);
+    assert_eq!(mime.to_string(), "text/html; charset=UTF-8");
+    assert_eq!(mime, parse("text/html;charset=UTF-8"));
+}
+
+#[test]
+fn empty_parameters_and_spaces_around_values_are_accepted() {
+    let expected = parse("text/plain; charset=utf-8");
+
+    for value in [
+        "text/plain; charset=utf-8;",
+        "text/plain; charset=utf-8; ",
+        "text/plain;; charset=utf-8",
+        "text/plain; charset = utf-8",
+        "text/plain;charset= utf-8 ",
+    ] {
+        assert_eq!(parse(value), expected, "{:?}", value);
+    }
+    assert!(parse("text/plain;").parameters().is_empty());
+    assert_eq!(
+        parse(r#"text/plain; name = "two words" ;"#).parameter("name"),
+        Some("two words")
+    );
+}
+
+#[test]
+fn parameters_can_be_quoted() {
+    let mime = parse(r#"multipart/form-data; boundary="a;b \"c\""; charset=utf-8"#);
+
+    assert_eq!(mime.parameter("boundary"), Some(r#"a;b "c""#));
+    assert_eq!(mime.parameter("charset"), Some("utf-8"));
+    assert_eq!(
+        mime.to_string(),
+        r#"multipart/form-data; boundary="a;b \"c\""; charset=utf-8"#
+    );
+    assert_eq!(parse(&mime.to_string()), mime);
+
+    // Values that are not tokens are quoted again
+    assert_eq!(
+        parse(r#"text/plain; name="two words""#).to_string(),
+        r#"text/plain; name="two words""#
+    );
+    assert_eq!(
+        parse(r#"text/plain; name="""#).to_string(),
+        r#"text/plain; name="""#
+    );
+}
+
+#[test]
+fn invalid_media_types_are_rejected() {
+    let too_long = format!("text/{}", "a".repeat(128));
+    let invalid = [
+        "",
+        "text",
+        "text/",
+        "/plain",
+        "text/plain/html",
+        "te xt/plain",
+        "-text/plain",
+        "text/.plain",
+        "text/pl@in",
+        "tëxt/plain",
+        too_long.as_str(),
+        "text/plain; charset",
+        "text/plain; =utf-8",
+        "text/plain; char set=utf-8",
+        "text/plain; charset=",
+        "text/plain; charset= ;",
+        "text/plain; charset=utf 8",
+        "text/plain; charset=\"utf-8",
+        "text/plain; charset=\"a\"b\"",
+        "text/plain; charset=\"\u{7}\"",
+    ];
+
+    for value in invalid {
+        assert!(
+            matches!(
+                value.parse::<MimeType>(),
+                Err(PoseidonError::InvalidMimeType(_))
+            ),
+            "{:?}",
+            value
+        );
+    }
+
+    assert!("x-custom/x-type+suffix".parse::<MimeType>().is_ok());
+    assert!(format!("text/{}", "a".repeat(127))
+        .parse::<MimeType>()
+        .is_ok());
+}
+
+#[test]
+fn user_data_stores_the_canonical_form() {
+    let data = UserData::new("Text/Plain;  Charset=utf-8", b"hello".to_vec()).unwrap();
+
+    assert_eq!(data.mime, "text/plain; charset=utf-8");
+    assert_eq!(
+        data.mime_type().unwrap(),
+        parse("text/plain; charset=utf-8")
+    );
+    assert!(UserData::new("text", Vec::new()).is_err());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn media_types_serialize_as_strings() {
+    let mime = parse("text/plain; charset=utf-8");
+    let json = serde_json::to_string(&mime).unwrap();
+
+    assert_eq!(json, r#""text/plain; charset=utf-8""#);
+    assert_eq!(serde_json::from_str::<MimeType>(&json).unwrap(), mime);
+    assert!(serde_json::from_str::<MimeType>(r#""text""#).is_err());
+}
+
+#[test]
+fn signatures_are_sniffed() {
+    let signatures: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", "image/png"),
+        (b"\xff\xd8\xff\xe0\0\x10JFIF", "image/jpeg"),
+        (b"GIF87a\x01\0\x01\0", "image/gif"),
+        (b"GIF89a\x01\0\x01\0", "image/gif"),
+        (b"RIFF\x24\0\0\0WEBPVP8 ", "image/webp"),
+        (b"%PDF-1.7\n", "application/pdf"),
+        (b"PK\x03\x04\x14\0", "application/zip"),
+        (b"\x1f\x8b\x08\0", "application/gzip"),
+        (b"\x28\xb5\x2f\xfd\x04\0", "application/zstd"),
+        (b"\0asm\x01\0\0\0", "application/wasm"),
+        (b"OggS\0\x02", "audio/ogg"),
+        (b"ID3\x04\0\0", "audio/mpeg"),
+        (b"RIFF\x24\0\0\0WAVEfmt ", "audio/wav"),
+    ];
+
+    for (bytes, mime) in signatures {
+        assert_eq!(sniffed(bytes).as_deref(), Some(*mime), "{:?}", bytes);
+    }
+
+    assert_eq!(sniffed(b""), None);
+    assert_eq!(sniffed(b"hello"), None);
+    // Too short to hold the whole signature
+    assert_eq!(sniffed(b"\x89PNG"), None);
+    assert_eq!(sniffed(b"RIFF\x24\0\0\0WEB"), None);
+}
+
+#[test]
+fn riff_formats_need_the_riff_header() {
+    // "WEBP" and "WAVE" at offset 8 of plain text
+    let text = b"Convert WEBP images";
+    assert_eq!(sniffed(text), None);
+    assert_eq!(sniffed(b"Convert WAVE files"), None);
+    assert_eq!(sniffed(b"RIFX\x24\0\0\0WAVEfmt "), None);
+
+    let data = UserData::new("text/plain", text.to_vec()).unwrap();
+    assert_eq!(data.check_mime_matches_content(), Ok(()));
+}
+
+#[test]
+fn declared_types_are_checked_against_the_content() {
+    let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+
+    let data = UserData::new("image/png", png.clone()).unwrap();
+    assert_eq!(data.check_mime_matches_content(), Ok(()));
+
+    let data = UserData::new("image/jpeg; q=1", png).unwrap();
+    assert_eq!(
+        data.check_mime_matches_content(),
+        Err(PoseidonError::MimeTypeMismatch {
+            declared: "image/jpeg".to_owned(),
+            detected: "image/png".to_owned(),
+        })
+    );
+
+    // Zip based formats and content without a signature are accepted
+    let docx = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+    let data = UserData::new(docx, b"PK\x03\x04\x14\0".to_vec()).unwrap();
+    assert_eq!(data.check_mime_matches_content(), Ok(()));
+    let data = UserData::new("application/epub+zip", b"PK\x03\x04".to_vec()).unwrap();
+    assert_eq!(data.check_mime_matches_content(), Ok(()));
+    let data = UserData::new("image/png", b"not an image".to_vec()).unwrap();
+    assert_eq!(data.check_mime_matches_content(), Ok(()));
+}
@@ -0,0 +1,136 @@
+#![cfg(feature = "mnemonic")]
+
+use poseidon_common::{derive_ed25519_keypair, DerivationPath, Mnemonic, PoseidonError};
+
+// SLIP-0010 test vector 1 for ed25519
+// https://github.com/satoshilabs/slips/blob/master/slip-0010.md#test-vector-1-for-ed25519
+const SLIP10_SEED: &str = "000102030405060708090a0b0c0d0e0f";
+const SLIP10_VECTORS: &[(&str, &str, &str)] = &[
+    (
+        "m",
+        "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+        "a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed",
+    ),
+    (
+        "m/0'",
+        "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+        "8c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c",
+    ),
+    (
+        "m/0'/1'",
+        "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+        "1932a5270f335bed617d5b935c80aedb1a35bd9fc1e31acafd5372c30f5c1187",
+    ),
+    (
+        "m/0'/1'/2'",
+        "92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9",
+        "ae98736566d30ed0e9d2f4486a64bc95740d89c7db33f52121f8ea8f76ff0fc1",
+    ),
+    (
+        "m/0'/1'/2'/2'",
+        "30d1dc7e5fc04c31219ab25a27ae00b50f6fd66622f6e9c913253d6511d1e662",
+        "8abae2d66361c879b900d204ad2cc4984fa2aa344dd7ddc46007329ac76c429c",
+    ),
+    (
+        "m/0'/1'/2'/2'/1000000000'",
+        "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+        "3c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a",
+    ),
+];
+
+#[test]
+fn slip10_ed25519_test_vector_1() {
+    let seed = hex::decode(SLIP10_SEED).unwrap();
+
+    for (path, secret, public) in SLIP10_VECTORS {
+        let path: DerivationPath = path.parse().unwrap();
+        let keypair = derive_ed25519_keypair(&seed, &path);
+
+        assert_eq!(hex::encode(keypair.seed().as_ref()), *secret, "{}", path);
+        assert_eq!(hex::encode(keypair.public_key()), *public, "{}", path);
+    }
+}
+
+// BIP39 test vector from https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+#[test]
+fn bip39_seed_test_vector() {
+    let mnemonic = Mnemonic::parse(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+    )
+    .unwrap();
+
+    assert_eq!(
+        hex::encode(mnemonic.to_seed("TREZOR").as_ref()),
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e5349553\
+         1f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+    );
+}
+
+// The address `solana-keygen recover 'prompt://?key=0/0'` and Phantom derive
+// for the first account of this mnemonic, without a passphrase
+#[test]
+fn solana_account_0_matches_solana_keygen_and_phantom() {
+    let mnemonic = Mnemonic::parse(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+    )
+    .unwrap();
+    let path: DerivationPath = "m/44'/501'/0'/0'".parse().unwrap();
+    let keypair = mnemonic.to_keypair_with_path("", &path);
+
+    assert_eq!(
+        keypair.public_key().to_base58(),
+        "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk"
+    );
+    assert_eq!(mnemonic.to_keypair("", 0).unwrap(), keypair);
+}
+
+#[test]
+fn mnemonic_errors() {
+    assert_eq!(
+        Mnemonic::parse("abandon abandon abandon").unwrap_err(),
+        PoseidonError::MnemonicInvalidWordCount(3)
+    );
+    assert_eq!(
+        Mnemonic::parse(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon poseidon",
+        )
+        .unwrap_err(),
+        PoseidonError::MnemonicUnknownWord { index: 11 }
+    );
+    assert_eq!(
+        Mnemonic::parse(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+        )
+        .unwrap_err(),
+        PoseidonError::MnemonicInvalidChecksum
+    );
+}
+
+#[test]
+fn derivation_paths() {
+    let path: DerivationPath = "m/44'/501'/0'/0'".parse().unwrap();
+    assert_eq!(path, DerivationPath::solana(0).unwrap());
+    assert_eq!(path.to_string(), "m/44'/501'/0'/0'");
+
+    for invalid in ["44'/501'", "m/44'/501", "m/44'/x'", "m/2147483648'", "m//"] {
+        assert!(matches!(
+            invalid.parse::<DerivationPath>(),
+            Err(PoseidonError::InvalidDerivationPath(_))
+        ));
+    }
+}
+
+#[test]
+fn generated_mnemonic_round_trips() {
+    let mnemonic = Mnemonic::generate(24).unwrap();
+    let parsed = Mnemonic::parse(&mnemonic.phrase()).unwrap();
+
+    assert_eq!(
+        mnemonic.to_keypair("", 0).unwrap(),
+        parsed.to_keypair("", 0).unwrap()
+    );
+    assert_ne!(
+        mnemonic.to_keypair("", 0).unwrap(),
+        mnemonic.to_keypair("", 1).unwrap()
+    );
+}
@@ -0,0 +1,121 @@
+use poseidon_common::{
+    Ed25519PublicKey, InstructionError, ProgramErrorInfo, ProgramErrorRegistry, TransactionError,
+    SPL_ASSOCIATED_TOKEN_PROGRAM_ID, SPL_TOKEN_2022_PROGRAM_ID, SPL_TOKEN_PROGRAM_ID,
+};
+
+fn program_id(value: &str) -> Ed25519PublicKey {
+    value.parse().unwrap()
+}
+
+fn custom_program() -> Ed25519PublicKey {
+    Ed25519PublicKey::new([7; 32])
+}
+
+fn name(registry: &ProgramErrorRegistry, program_id: &Ed25519PublicKey, code: u32) -> String {
+    registry.lookup(program_id, code).unwrap().name
+}
+
+#[test]
+fn builtin_errors_are_found_by_program_and_code() {
+    let registry = ProgramErrorRegistry::with_builtin();
+    let token = program_id(SPL_TOKEN_PROGRAM_ID);
+
+    assert_eq!(
+        registry.lookup(&token, 1),
+        Some(ProgramErrorInfo::new(
+            "InsufficientFunds",
+            "Insufficient funds"
+        ))
+    );
+    assert_eq!(name(&registry, &token, 0), "NotRentExempt");
+    assert_eq!(name(&registry, &token, 19), "NonNativeNotSupported");
+    assert_eq!(
+        name(&registry, &program_id(SPL_ASSOCIATED_TOKEN_PROGRAM_ID), 0),
+        "InvalidOwner"
+    );
+}
+
+#[test]
+fn unknown_programs_and_codes_are_not_found() {
+    let registry = ProgramErrorRegistry::with_builtin();
+
+    assert_eq!(registry.lookup(&program_id(SPL_TOKEN_PROGRAM_ID), 20), None);
+    assert_eq!(registry.lookup(&custom_program(), 1), None);
+    // Token-2022 defines more codes than the builtin table knows
+    assert_eq!(
+        registry.lookup(&program_id(SPL_TOKEN_2022_PROGRAM_ID), 1),
+        None
+    );
+    assert_eq!(
+        ProgramErrorRegistry::new().lookup(&program_id(SPL_TOKEN_PROGRAM_ID), 1),
+        None
+    );
+}
+
+#[test]
+fn anchor_programs_fall_back_to_framework_errors() {
+    let mut registry = ProgramErrorRegistry::new();
+    let program = custom_program();
+    registry.register_all(
+        program,
+        [
+            (
+                6000,
+                ProgramErrorInfo::new("SlippageExceeded", "Slippage exceeded"),
+            ),
+            (
+                2003,
+                ProgramErrorInfo::new("Overridden", "Defined by the program"),
+            ),
+        ],
+    );
+
+    // Framework codes only apply once the program is marked as Anchor
+    assert_eq!(registry.lookup(&program, 2000), None);
+    registry.register_anchor_program(program);
+
+    assert_eq!(name(&registry, &program, 6000), "SlippageExceeded");
+    assert_eq!(name(&registry, &program, 2000), "ConstraintMut");
+    assert_eq!(name(&registry, &program, 3012), "AccountNotInitialized");
+    // Codes registered for the program win over the framework ones
+    assert_eq!(name(&registry, &program, 2003), "Overridden");
+    assert_eq!(registry.lookup(&program, 6001), None);
+    assert_eq!(registry.lookup(&program, 2), None);
+}
+
+#[test]
+fn failed_transactions_are_described() {
+    let mut registry = ProgramErrorRegistry::with_builtin();
+    let program = custom_program();
+    registry.register(
+        program,
+        6001,
+        ProgramErrorInfo::new("SlippageExceeded", "Slippage exceeded"),
+    );
+    let program_ids = [program_id(SPL_TOKEN_PROGRAM_ID), program];
+
+    let error = TransactionError::InstructionError(1, InstructionError::Custom(6001));
+    let description = registry.describe(&error, &program_ids).unwrap();
+    assert_eq!(description.instruction_index, 1);
+    assert_eq!(description.program_id, program);
+    assert_eq!(
+        description.to_string(),
+        "Error processing Instruction 1: SlippageExceeded (0x1771): Slippage exceeded"
+    );
+
+    let error = TransactionError::InstructionError(0, InstructionError::Custom(1));
+    assert_eq!(
+        registry.describe(&error, &program_ids).unwrap().error.name,
+        "InsufficientFunds"
+    );
+
+    // Unknown codes, missing program IDs and other errors
+    for error in [
+        TransactionError::InstructionError(1, InstructionError::Custom(6002)),
+        TransactionError::InstructionError(2, InstructionError::Custom(1)),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData),
+        TransactionError::BlockhashNotFound,
+    ] {
+        assert_eq!(registry.describe(&error, &program_ids), None, "{:?}", error);
+    }
+}
@@ -0,0 +1,286 @@
+//! `RpcClient` requests and responses against a mock RPC node
+
+#![cfg(feature = "http")]
+
+mod common;
+
+use common::{error, mock_node, result};
+use poseidon_common::{
+    AccountInfo, Commitment, Ed25519Keypair, InstructionError, LatestBlockhash, PoseidonError,
+    RpcClient, SignatureStatus, TransactionError,
+};
+use serde_json::{json, Value};
+
+fn client<F>(respond: F) -> RpcClient
+where
+    F: Fn(&str, &Value) -> (u16, String) + Send + 'static,
+{
+    RpcClient::new(mock_node(move |request| {
+        respond(request["method"].as_str().unwrap(), &request["params"])
+    }))
+}
+
+#[test]
+fn accounts_and_balances() {
+    let owner = Ed25519Keypair::from_seed(&[1; 32]).public_key();
+    let account = Ed25519Keypair::from_seed(&[2; 32]).public_key();
+    let missing = Ed25519Keypair::from_seed(&[3; 32]).public_key();
+
+    let client = client(move |method, params| {
+        let context = json!({ "slot": 42 });
+        match method {
+            "getAccountInfo" if params[0] == json!(account.to_base58()) => {
+                assert_eq!(params[1]["encoding"], "base64");
+                assert_eq!(params[1]["commitment"], "finalized");
+                result(json!({
+                    "context": context,
+                    "value": {
+                        "data": ["AQID", "base64"],
+                        "executable": false,
+                        "lamports": 1_000_000,
+                        "owner": owner.to_base58(),
+                        "rentEpoch": u64::MAX,
+                        "space": 3
+                    }
+                }))
+            }
+            "getAccountInfo" => result(json!({ "context": context, "value": null })),
+            "getBalance" => result(json!({ "context": context, "value": 5_000 })),
+            _ => (404, String::new()),
+        }
+    })
+    .with_commitment(Commitment::Finalized);
+
+    assert_eq!(
+        client.get_account_info(&account).unwrap(),
+        Some(AccountInfo {
+            lamports: 1_000_000,
+            owner,
+            data: vec![1, 2, 3],
+            executable: false,
+            rent_epoch: u64::MAX,
+        })
+    );
+    assert_eq!(client.get_account_info(&missing).unwrap(), None);
+    assert_eq!(client.get_balance(&account).unwrap(), 5_000);
+}
+
+#[test]
+fn blockhashes_and_statuses() {
+    let keypair = Ed25519Keypair::from_seed(&[1; 32]);
+    let confirmed = keypair.sign(b"confirmed");
+    let unknown = keypair.sign(b"unknown");
+
+    let client = client(|method, params| match method {
+        "getLatestBlockhash" => result(json!({
+            "context": { "slot": 42 },
+            "value": {
+                "blockhash": "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N",
+                "lastValidBlockHeight": 3090
+            }
+        })),
+        "getSignatureStatuses" => {
+            assert_eq!(params[1]["searchTransactionHistory"], true);
+            result(json!({
+                "context": { "slot": 82 },
+                "value": [
+                    {
+                        "slot": 72,
+                        "confirmations": 10,
+                        "err": { "InstructionError": [0, { "Custom": 1 }] },
+                        "status": { "Err": { "InstructionError": [0, { "Custom": 1 }] } },
+                        "confirmationStatus": "confirmed"
+                    },
+                    null
+                ]
+            }))
+        }
+        _ => (404, String::new()),
+    });
+
+    assert_eq!(
+        client.get_latest_blockhash().unwrap(),
+        LatestBlockhash {
+            blockhash: "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N".to_owned(),
+            last_valid_block_height: 3090,
+        }
+    );
+
+    let statuses = client
+        .get_signature_statuses(&[confirmed, unknown])
+        .unwrap();
+    let status = SignatureStatus {
+        slot: 72,
+        confirmations: Some(10),
+        err: Some(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(1),
+        )),
+        confirmation_status: Some(Commitment::Confirmed),
+    };
+    assert!(status.satisfies(Commitment::Processed));
+    assert!(!status.satisfies(Commitment::Finalized));
+    assert_eq!(statuses, vec![Some(status), None]);
+}
+
+#[test]
+fn sending_transactions() {
+    let signature = Ed25519Keypair::from_seed(&[1; 32]).sign(b"transaction");
+
+    let client = client(move |_, params| match params[0].as_str().unwrap() {
+        // base64 of `[1, 2, 3]`
+        "AQID" => result(json!(signature.to_base58())),
+        _ => error(json!({
+            "code": -32002,
+            "message": "Transaction simulation failed: Attempt to debit an account but found no record of a prior credit.",
+            "data": {
+                "accounts": null,
+                "err": "AccountNotFound",
+                "logs": [],
+                "unitsConsumed": 0
+            }
+        })),
+    });
+
+    assert_eq!(client.send_transaction(&[1, 2, 3]).unwrap(), signature);
+    assert_eq!(
+        client.send_transaction(&[4, 5, 6]),
+        Err(PoseidonError::Tx(TransactionError::AccountNotFound))
+    );
+}
+
+#[test]
+fn fetching_transactions() {
+    let keypair = Ed25519Keypair::from_seed(&[1; 32]);
+    let known = keypair.sign(b"known");
+
+    let client = client(move |_, params| {
+        // Transactions are only served from confirmed blocks
+        assert_eq!(params[1]["commitment"], "confirmed");
+        assert_eq!(params[1]["maxSupportedTransactionVersion"], 0);
+
+        if params[0] == json!(known.to_base58()) {
+            result(json!({
+                "slot": 430,
+                "blockTime": 1_700_000_000,
+                "meta": {
+                    "err": null,
+                    "fee": 5_000,
+                    "logMessages": ["Program 11111111111111111111111111111111 success"],
+                    "preBalances": [],
+                    "postBalances": []
+                },
+                "transaction": ["AQID", "base64"],
+                "version": 0
+            }))
+        } else {
+            result(Value::Null)
+        }
+    })
+    .with_commitment(Commitment::Processed);
+
+    let transaction = client.get_transaction(&known).unwrap().unwrap();
+    assert_eq!(transaction.slot, 430);
+    assert_eq!(transaction.block_time, Some(1_700_000_000));
+    assert_eq!(transaction.transaction, vec![1, 2, 3]);
+    assert_eq!(transaction.err, None);
+    assert_eq!(transaction.fee, 5_000);
+    assert_eq!(transaction.log_messages.len(), 1);
+
+    assert_eq!(
+        client.get_transaction(&keypair.sign(b"unknown")).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn unknown_transaction_errors_are_kept() {
+    let keypair = Ed25519Keypair::from_seed(&[1; 32]);
+
+    let client = client(|method, _| match method {
+        "getSignatureStatuses" => result(json!({
+            "context": { "slot": 82 },
+            "value": [
+                {
+                    "slot": 72,
+                    "confirmations": null,
+                    "err": "SomethingNew",
+                    "confirmationStatus": "finalized"
+                },
+                {
+                    "slot": 73,
+                    "confirmations": null,
+                    "err": { "InstructionError": [1, "SomethingNew"] },
+                    "confirmationStatus": "finalized"
+                }
+            ]
+        })),
+        "getTransaction" => result(json!({
+            "slot": 430,
+            "blockTime": null,
+            "meta": {
+                "err": { "SomethingNew": { "account_index": 2 } },
+                "fee": 5_000,
+                "logMessages": null
+            },
+            "transaction": ["AQID", "base64"]
+        })),
+        _ => (404, String::new()),
+    });
+
+    let errors: Vec<_> = client
+        .get_signature_statuses(&[keypair.sign(b"first"), keypair.sign(b"second")])
+        .unwrap()
+        .into_iter()
+        .map(|status| status.unwrap().err)
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            Some(TransactionError::Unknown(r#""SomethingNew""#.to_owned())),
+            Some(TransactionError::Unknown(
+                r#"{"InstructionError":[1,"SomethingNew"]}"#.to_owned()
+            )),
+        ]
+    );
+
+    let transaction = client
+        .get_transaction(&keypair.sign(b"first"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        transaction.err,
+        Some(TransactionError::Unknown(
+            r#"{"SomethingNew":{"account_index":2}}"#.to_owned()
+        ))
+    );
+}
+
+#[test]
+fn node_errors() {
+    let client = client(|method, _| match method {
+        "getSlot" => error(json!({ "code": -32601, "message": "Method not found" })),
+        "getHealth" => result(json!("behind")),
+        _ => (429, "Too many requests".to_owned()),
+    });
+
+    let error = client.get_health().unwrap_err();
+    assert_eq!(
+        error,
+        PoseidonError::NodeUnhealthy("the node reports `behind`".to_owned())
+    );
+    assert!(error.is_retryable());
+    assert_eq!(error.http_status(), 503);
+
+    let error = client.get_slot().unwrap_err();
+    assert!(
+        matches!(&error, PoseidonError::Json(json_error) if json_error.code == -32601),
+        "{:?}",
+        error
+    );
+    assert!(!error.is_retryable());
+
+    let error = client.get_latest_blockhash().unwrap_err();
+    assert!(error.is_retryable(), "{:?}", error);
+    assert_eq!(error.http_status(), 503);
+}
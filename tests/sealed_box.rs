@@ -0,0 +1,118 @@
+#![cfg(feature = "sealed_box")]
+
+use poseidon_common::{
+    open_bytes, seal_bytes, Ed25519Keypair, Ed25519PublicKey, PoseidonError, UserData,
+    SEALED_BOX_VERSION,
+};
+
+/// Version byte, ephemeral public key and nonce
+const OVERHEAD: usize = 1 + 32 + 24;
+const TAG_LENGTH: usize = 16;
+
+fn recipient() -> Ed25519Keypair {
+    Ed25519Keypair::from_seed(&[3; 32])
+}
+
+#[test]
+fn sealed_boxes_round_trip() {
+    let keypair = recipient();
+
+    for plaintext in [&b""[..], b"hello", &[7; 4096]] {
+        let sealed = seal_bytes(&keypair.public_key(), plaintext).unwrap();
+
+        assert_eq!(sealed[0], SEALED_BOX_VERSION);
+        assert_eq!(sealed.len(), OVERHEAD + plaintext.len() + TAG_LENGTH);
+        assert_eq!(open_bytes(&keypair, &sealed).unwrap(), plaintext);
+    }
+
+    // A fresh ephemeral key and nonce every time
+    let first = seal_bytes(&keypair.public_key(), b"hello").unwrap();
+    let second = seal_bytes(&keypair.public_key(), b"hello").unwrap();
+    assert_ne!(first, second);
+
+    let data = UserData {
+        mime: "text/plain".to_owned(),
+        data: b"hello".to_vec(),
+    };
+    let sealed = data.seal(&keypair.public_key()).unwrap();
+    let opened = UserData::open(&keypair, &sealed).unwrap();
+    assert_eq!((opened.mime, opened.data), (data.mime, data.data));
+}
+
+#[test]
+fn only_the_recipient_can_open() {
+    let sealed = seal_bytes(&recipient().public_key(), b"hello").unwrap();
+
+    assert_eq!(
+        open_bytes(&Ed25519Keypair::from_seed(&[4; 32]), &sealed),
+        Err(PoseidonError::DecryptionFailed)
+    );
+}
+
+#[test]
+fn tampering_is_detected() {
+    let keypair = recipient();
+    let sealed = seal_bytes(&keypair.public_key(), b"hello").unwrap();
+
+    // The tag, the ciphertext, the nonce and the authenticated ephemeral key
+    for index in [sealed.len() - 1, OVERHEAD, 1 + 32, 1] {
+        let mut tampered = sealed.clone();
+        tampered[index] ^= 1;
+
+        assert_eq!(
+            open_bytes(&keypair, &tampered),
+            Err(PoseidonError::DecryptionFailed),
+            "byte {}",
+            index
+        );
+    }
+}
+
+#[test]
+fn malformed_boxes_are_rejected() {
+    let keypair = recipient();
+    let sealed = seal_bytes(&keypair.public_key(), b"hello").unwrap();
+
+    for length in [0, 1, OVERHEAD, OVERHEAD + TAG_LENGTH - 1] {
+        assert_eq!(
+            open_bytes(&keypair, &sealed[..length]),
+            Err(PoseidonError::InvalidEncryptedData),
+            "length {}",
+            length
+        );
+    }
+    // Truncated, but long enough to reach the authentication
+    assert_eq!(
+        open_bytes(&keypair, &sealed[..sealed.len() - 1]),
+        Err(PoseidonError::DecryptionFailed)
+    );
+
+    for version in [0, SEALED_BOX_VERSION + 1, u8::MAX] {
+        let mut unknown = sealed.clone();
+        unknown[0] = version;
+
+        assert_eq!(
+            open_bytes(&keypair, &unknown),
+            Err(PoseidonError::InvalidEncryptedData)
+        );
+    }
+}
+
+#[test]
+fn invalid_recipients_are_rejected() {
+    // y = 2 has no x coordinate on the curve
+    let mut not_a_point = [0; 32];
+    not_a_point[0] = 2;
+    assert_eq!(
+        seal_bytes(&Ed25519PublicKey::new(not_a_point), b"hello"),
+        Err(PoseidonError::InvalidEd25519PublicKey)
+    );
+
+    // The identity, a point of low order
+    let mut identity = [0; 32];
+    identity[0] = 1;
+    assert_eq!(
+        seal_bytes(&Ed25519PublicKey::new(identity), b"hello"),
+        Err(PoseidonError::InvalidEd25519PublicKey)
+    );
+}
@@ -0,0 +1,375 @@
+//! Golden encodings of `PoseidonError`. The feature matrix runs this suite
+//! under every feature set, so each build must produce and accept exactly
+//! these bytes.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use poseidon_common::{
+    HttpError, InstructionError, JsonError, JsonErrorData, PoseidonError, PoseidonErrorKind,
+    PoseidonIoError, RawOsError, RustlsError, SctError, StoreErr, TransactionError,
+    MAX_CONTEXT_DEPTH,
+};
+use std::fmt::Debug;
+
+fn golden() -> Vec<(PoseidonError, &'static str, &'static str)> {
+    vec![
+        (
+            PoseidonError::MissingKeypair,
+            "e90300000f0000006d697373696e67206b65797061697200000000",
+            r#""MissingKeypair""#,
+        ),
+        (
+            PoseidonError::ChunkOutOfOrder {
+                expected: 1,
+                found: 3,
+            },
+            "d3070000250000006578706563746564206368756e6b203120627574207265636569766564206368756e6b2033080000000100000003000000",
+            r#"{"ChunkOutOfOrder":{"expected":1,"found":3}}"#,
+        ),
+        (
+            PoseidonError::IoErr(PoseidonIoError {
+                kind: PoseidonErrorKind::TimedOut,
+                raw_os_error: Some(RawOsError {
+                    code: 110,
+                    os: "linux".to_owned(),
+                }),
+                message: "Connection timed out (os error 110)".to_owned(),
+                path: Some("/tmp/id.json".to_owned()),
+            }),
+            "bf0b00003e000000492f4f206572726f723a20602f746d702f69642e6a736f6e603a20436f6e6e656374696f6e2074696d6564206f757420286f73206572726f722031313029470000000d016e000000050000006c696e757823000000436f6e6e656374696f6e2074696d6564206f757420286f73206572726f722031313029010c0000002f746d702f69642e6a736f6e",
+            r#"{"IoErr":{"kind":"TimedOut","raw_os_error":{"code":110,"os":"linux"},"message":"Connection timed out (os error 110)","path":"/tmp/id.json"}}"#,
+        ),
+        (
+            PoseidonError::Tx(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(6001),
+            )),
+            "a30f00004f0000007472616e73616374696f6e206572726f723a204572726f722070726f63657373696e6720496e737472756374696f6e20303a20637573746f6d2070726f6772616d206572726f723a203078313737310700000008001971170000",
+            r#"{"Tx":{"InstructionError":[0,{"Custom":6001}]}}"#,
+        ),
+        (
+            PoseidonError::Rustls(RustlsError::DecryptError),
+            "0410000028000000544c53206572726f723a2063616e6e6f74206465637279707420706565722773206d6573736167650100000006",
+            r#"{"Rustls":"DecryptError"}"#,
+        ),
+        (
+            PoseidonError::Http(HttpError::TooManyRedirections),
+            "051000002100000048545450206572726f723a20746f6f206d616e79207265646972656374696f6e730100000008",
+            r#"{"Http":"TooManyRedirections"}"#,
+        ),
+        (
+            PoseidonError::Json(JsonError {
+                code: -32005,
+                message: "Node is unhealthy".to_owned(),
+                data: None,
+            }),
+            "061000002f0000004a534f4e2d525043206572726f723a204e6f646520697320756e6865616c7468792028636f6465202d33323030352917000000fb82110000004e6f646520697320756e6865616c746879",
+            r#"{"Json":{"code":-32005,"message":"Node is unhealthy"}}"#,
+        ),
+        (
+            PoseidonError::NodeUnhealthy("the node reports `behind`".to_owned()),
+            "0a1000002d000000756e6865616c74687920525043206e6f64653a20746865206e6f6465207265706f7274732060626568696e64601d00000019000000746865206e6f6465207265706f7274732060626568696e6460",
+            r#"{"NodeUnhealthy":"the node reports `behind`"}"#,
+        ),
+        (
+            PoseidonError::Unspecified("oops".to_owned()).context("syncing"),
+            "292300000d00000073796e63696e673a206f6f707327000000010000000700000073796e63696e6728230000040000006f6f707308000000040000006f6f7073",
+            r#"{"Context":{"context":["syncing"],"source":{"Unspecified":"oops"}}}"#,
+        ),
+    ]
+}
+
+#[test]
+fn borsh_encoding_is_stable() {
+    for (error, encoded, _) in golden() {
+        assert_eq!(
+            hex::encode(error.try_to_vec().unwrap()),
+            encoded,
+            "{:?}",
+            error
+        );
+        assert_eq!(
+            PoseidonError::try_from_slice(&hex::decode(encoded).unwrap()).unwrap(),
+            error
+        );
+    }
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn serde_json_encoding_is_stable() {
+    for (error, _, json) in golden() {
+        assert_eq!(serde_json::to_string(&error).unwrap(), json);
+        assert_eq!(serde_json::from_str::<PoseidonError>(json).unwrap(), error);
+    }
+}
+
+#[test]
+fn unknown_codes_keep_code_and_message() {
+    let mut encoded = 7777u32.try_to_vec().unwrap();
+    encoded.extend("from the future".to_owned().try_to_vec().unwrap());
+    encoded.extend(vec![1u8, 2, 3].try_to_vec().unwrap());
+
+    let error = PoseidonError::try_from_slice(&encoded).unwrap();
+    assert_eq!(
+        error,
+        PoseidonError::Unknown {
+            code: 7777,
+            message: "from the future".to_owned()
+        }
+    );
+    assert_eq!(error.try_to_vec().unwrap()[..4], 7777u32.to_le_bytes());
+}
+
+#[test]
+fn undecodable_payloads_degrade_to_unknown() {
+    let mut encoded = 1100u32.try_to_vec().unwrap();
+    encoded.extend("bad word count".to_owned().try_to_vec().unwrap());
+    encoded.extend(vec![0xffu8].try_to_vec().unwrap());
+
+    assert_eq!(
+        PoseidonError::try_from_slice(&encoded).unwrap(),
+        PoseidonError::Unknown {
+            code: 1100,
+            message: "bad word count".to_owned()
+        }
+    );
+
+    // A transaction error added in a newer version
+    let mut encoded = 4003u32.try_to_vec().unwrap();
+    encoded.extend("from the future".to_owned().try_to_vec().unwrap());
+    encoded.extend(vec![200u8].try_to_vec().unwrap());
+
+    assert_eq!(
+        PoseidonError::try_from_slice(&encoded).unwrap(),
+        PoseidonError::Unknown {
+            code: 4003,
+            message: "from the future".to_owned()
+        }
+    );
+}
+
+#[test]
+fn context_round_trips() {
+    let error = PoseidonError::DataIdMismatch
+        .context("verifying")
+        .context("syncing");
+    assert_eq!(error.context_chain(), ["verifying", "syncing"]);
+
+    let decoded = PoseidonError::try_from_slice(&error.try_to_vec().unwrap()).unwrap();
+    assert_eq!(decoded, error);
+    assert_eq!(decoded.code(), 2000);
+    assert_eq!(decoded.root_cause(), &PoseidonError::DataIdMismatch);
+
+    // Context built by hand can nest, up to the maximum depth
+    let mut nested = PoseidonError::DataIdMismatch;
+    for depth in 0..MAX_CONTEXT_DEPTH {
+        nested = PoseidonError::Context {
+            context: vec![depth.to_string()],
+            source: Box::new(nested),
+        };
+    }
+    let decoded = PoseidonError::try_from_slice(&nested.try_to_vec().unwrap()).unwrap();
+    assert_eq!(decoded, nested);
+}
+
+#[test]
+fn json_errors_with_data_have_their_own_code() {
+    let error = PoseidonError::Json(JsonError {
+        code: -32002,
+        message: "Transaction simulation failed".to_owned(),
+        data: Some(JsonErrorData {
+            err: None,
+            logs: Some(vec!["Program log: hello".to_owned()]),
+            units_consumed: Some(150),
+        }),
+    });
+
+    let encoded = error.try_to_vec().unwrap();
+    assert_eq!(encoded[..4], 4105u32.to_le_bytes());
+    assert_eq!(error.code(), 4105);
+    assert_eq!(PoseidonError::try_from_slice(&encoded).unwrap(), error);
+
+    // Without data the payload stays `(code, message)`
+    let error = PoseidonError::Json(JsonError {
+        code: -32002,
+        message: "Transaction simulation failed".to_owned(),
+        data: None,
+    });
+    let mut payload = (-32002i16).try_to_vec().unwrap();
+    payload.extend(
+        "Transaction simulation failed"
+            .to_owned()
+            .try_to_vec()
+            .unwrap(),
+    );
+    assert!(error
+        .try_to_vec()
+        .unwrap()
+        .ends_with(&payload.try_to_vec().unwrap()));
+}
+
+/// `levels` of context around an empty `Unspecified`, encoded without
+/// building the nested error
+fn nested_context(levels: usize) -> Vec<u8> {
+    // Code, message and payload of the innermost error
+    let root = [
+        &9000u32.to_le_bytes()[..],
+        &[0; 4],
+        &4u32.to_le_bytes(),
+        &[0; 4],
+    ]
+    .concat();
+    // Each level adds its code, an empty message, the payload length and an
+    // empty context list
+    let level_len = 16;
+
+    let mut encoded = Vec::with_capacity(levels * level_len + root.len());
+    for level in (0..levels).rev() {
+        let payload_len = 4 + level * level_len + root.len();
+        encoded.extend(9001u32.to_le_bytes());
+        encoded.extend(0u32.to_le_bytes());
+        encoded.extend((payload_len as u32).to_le_bytes());
+        encoded.extend(0u32.to_le_bytes());
+    }
+    encoded.extend(root);
+
+    encoded
+}
+
+#[test]
+fn deeply_nested_context_is_cut_off() {
+    let decoded = PoseidonError::try_from_slice(&nested_context(MAX_CONTEXT_DEPTH)).unwrap();
+    assert_eq!(
+        decoded.root_cause(),
+        &PoseidonError::Unspecified(String::new())
+    );
+
+    // Deep enough to overflow the stack if it were decoded recursively
+    for levels in [MAX_CONTEXT_DEPTH + 1, 100_000] {
+        let mut error = PoseidonError::try_from_slice(&nested_context(levels)).unwrap();
+        for _ in 0..MAX_CONTEXT_DEPTH {
+            error = match error {
+                PoseidonError::Context { source, .. } => *source,
+                error => panic!("{:?}", error),
+            };
+        }
+        assert_eq!(
+            error,
+            PoseidonError::Unknown {
+                code: 9001,
+                message: String::new()
+            }
+        );
+    }
+}
+
+#[test]
+fn legacy_io_errors_decode_from_their_kind() {
+    let encoded = "ba0b000014000000492f4f206572726f723a2074696d6564206f7574010000000d";
+
+    assert_eq!(
+        PoseidonError::try_from_slice(&hex::decode(encoded).unwrap()).unwrap(),
+        PoseidonError::IoErr(PoseidonIoError {
+            kind: PoseidonErrorKind::TimedOut,
+            raw_os_error: None,
+            message: "timed out".to_owned(),
+            path: None,
+        })
+    );
+}
+
+#[test]
+fn untagged_os_codes_are_dropped() {
+    // A 3002 payload with the raw OS code of a version that did not record
+    // the OS
+    let encoded = "ba0b00003e000000492f4f206572726f723a20602f746d702f69642e6a736f6e603a20436f6e6e656374696f6e2074696d6564206f757420286f73206572726f7220313130293e0000000d016e00000023000000436f6e6e656374696f6e2074696d6564206f757420286f73206572726f722031313029010c0000002f746d702f69642e6a736f6e";
+
+    assert_eq!(
+        PoseidonError::try_from_slice(&hex::decode(encoded).unwrap()).unwrap(),
+        PoseidonError::IoErr(PoseidonIoError {
+            kind: PoseidonErrorKind::TimedOut,
+            raw_os_error: None,
+            message: "Connection timed out (os error 110)".to_owned(),
+            path: Some("/tmp/id.json".to_owned()),
+        })
+    );
+}
+
+#[test]
+fn io_errors_round_trip_into_std() {
+    let error = PoseidonIoError::from(std::io::Error::from_raw_os_error(2)).with_path("id.json");
+    assert_eq!(error.kind, PoseidonErrorKind::NotFound);
+    assert_eq!(error.raw_os_error, Some(RawOsError::new(2)));
+
+    let error = std::io::Error::from(error);
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(error.raw_os_error(), Some(2));
+
+    // Codes of another OS would name a different error here
+    let error = std::io::Error::from(PoseidonIoError {
+        kind: PoseidonErrorKind::TimedOut,
+        raw_os_error: Some(RawOsError {
+            code: 60,
+            os: "another-os".to_owned(),
+        }),
+        message: "Operation timed out (os error 60)".to_owned(),
+        path: Some("id.json".to_owned()),
+    });
+    assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+    assert_eq!(error.raw_os_error(), None);
+    assert_eq!(
+        error.to_string(),
+        "`id.json`: Operation timed out (os error 60)"
+    );
+
+    let error = std::io::Error::from(PoseidonIoError::from(std::io::Error::new(
+        std::io::ErrorKind::StorageFull,
+        "disk is full",
+    )));
+    assert_eq!(error.kind(), std::io::ErrorKind::StorageFull);
+    assert_eq!(error.to_string(), "disk is full");
+}
+
+/// Decodes every variant of `T` from its code followed by zeroed fields and
+/// checks that `code` returns it and that the encoding starts with it.
+/// Returns the number of variants.
+fn check_codes<T: BorshSerialize + BorshDeserialize + Debug>(code: fn(&T) -> u32) -> usize {
+    let mut variants = 0;
+
+    for wire_code in 0..=u8::MAX {
+        let mut bytes = vec![wire_code];
+        bytes.extend([0u8; 64]);
+
+        let value = match T::deserialize(&mut bytes.as_slice()) {
+            Ok(value) => value,
+            Err(_) => break,
+        };
+        assert_eq!(code(&value), u32::from(wire_code), "{:?}", value);
+        assert_eq!(value.try_to_vec().unwrap()[0], wire_code, "{:?}", value);
+        variants += 1;
+    }
+
+    variants
+}
+
+#[test]
+fn nested_errors_are_encoded_as_their_code() {
+    // A new variant changes these counts, it must take the next free code
+    assert_eq!(check_codes(PoseidonErrorKind::code), 40);
+    assert_eq!(check_codes(StoreErr::code), 9);
+    assert_eq!(check_codes(HttpError::code), 18);
+    assert_eq!(check_codes(RustlsError::code), 23);
+    assert_eq!(check_codes(SctError::code), 5);
+    assert_eq!(check_codes(TransactionError::code), 40);
+    assert_eq!(check_codes(InstructionError::code), 53);
+
+    // PoseidonError is encoded as its code followed by message and payload.
+    // `code` looks through context, which is encoded as its own code.
+    for (error, encoded, _) in golden() {
+        if !error.context_chain().is_empty() {
+            continue;
+        }
+        let encoded = hex::decode(encoded).unwrap();
+        assert_eq!(encoded[..4], error.code().to_le_bytes(), "{:?}", error);
+    }
+}